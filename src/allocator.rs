@@ -0,0 +1,208 @@
+use core::{alloc::Layout, fmt, ptr};
+
+extern crate alloc;
+
+/// The error returned when memory couldn't be allocated.
+#[expect(
+    clippy::exhaustive_structs,
+    reason = "Allocators outside this crate have to be able to return it"
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AllocError;
+
+impl fmt::Display for AllocError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("memory allocation failed")
+    }
+}
+
+impl core::error::Error for AllocError {}
+
+/// An allocator the pointer types can store their values in.
+///
+/// The pointer types never allocate zero-sized layouts, they use a dangling pointer instead.
+///
+/// # Safety
+/// Memory returned by `allocate` has to stay valid until it is passed to `deallocate`, even if the
+/// allocator is moved. The memory has to fit the layout it was allocated for.
+///
+/// Every clone of an allocator has to behave like the same allocator, as every `SharedPointer`
+/// and `WeakPointer` stores its own clone. Memory allocated by one clone can be freed by any other
+/// clone, and has to stay valid as long as any clone is alive, even after the clone that
+/// allocated it is dropped.
+pub unsafe trait Allocator {
+    /// Allocates memory for the layout, the size of the layout is never 0.
+    ///
+    /// # Errors
+    /// Returns an `AllocError` if no memory could be allocated.
+    fn allocate(&self, layout: Layout) -> Result<ptr::NonNull<u8>, AllocError>;
+
+    /// Allocates zeroed memory for the layout, the size of the layout is never 0.
+    ///
+    /// # Errors
+    /// Returns an `AllocError` if no memory could be allocated.
+    #[inline]
+    fn allocate_zeroed(&self, layout: Layout) -> Result<ptr::NonNull<u8>, AllocError> {
+        self.allocate(layout).inspect(|pointer| {
+            // Safety: The allocated memory fits the layout
+            unsafe { pointer.write_bytes(0, layout.size()) }
+        })
+    }
+
+    /// Frees memory allocated by this allocator.
+    ///
+    /// # Safety
+    /// The pointer has to be allocated by this allocator with the same layout.
+    unsafe fn deallocate(&self, pointer: ptr::NonNull<u8>, layout: Layout);
+}
+
+/// The global allocator, which is used by default.
+#[expect(
+    clippy::exhaustive_structs,
+    reason = "The global allocator will never have any fields"
+)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Global;
+
+/// Safety: The global allocator keeps memory valid until it is freed, and every copy of it is the
+/// same allocator.
+unsafe impl Allocator for Global {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Result<ptr::NonNull<u8>, AllocError> {
+        // Allocate memory
+        // Safety: Pointer will be checked for NULL before usage and the layout isn't zero-sized.
+        let pointer = unsafe { alloc::alloc::alloc(layout) };
+
+        // Store the pointer in a non-null pointer and return it
+        ptr::NonNull::new(pointer).ok_or(AllocError)
+    }
+
+    #[inline]
+    fn allocate_zeroed(&self, layout: Layout) -> Result<ptr::NonNull<u8>, AllocError> {
+        // Allocate zeroed memory, which may be faster than writing zeroes
+        // Safety: Pointer will be checked for NULL before usage and the layout isn't zero-sized.
+        let pointer = unsafe { alloc::alloc::alloc_zeroed(layout) };
+
+        // Store the pointer in a non-null pointer and return it
+        ptr::NonNull::new(pointer).ok_or(AllocError)
+    }
+
+    #[inline]
+    unsafe fn deallocate(&self, pointer: ptr::NonNull<u8>, layout: Layout) {
+        // Safety: The pointer was allocated with the same layout
+        unsafe { alloc::alloc::dealloc(pointer.as_ptr(), layout) }
+    }
+}
+
+/// Safety: The referenced allocator keeps memory valid until it is freed, and every copy of the
+/// reference refers to the same allocator.
+unsafe impl<A: Allocator + ?Sized> Allocator for &A {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Result<ptr::NonNull<u8>, AllocError> {
+        (**self).allocate(layout)
+    }
+
+    #[inline]
+    fn allocate_zeroed(&self, layout: Layout) -> Result<ptr::NonNull<u8>, AllocError> {
+        (**self).allocate_zeroed(layout)
+    }
+
+    #[inline]
+    unsafe fn deallocate(&self, pointer: ptr::NonNull<u8>, layout: Layout) {
+        // Safety: The caller guarantees the pointer was allocated by this allocator
+        unsafe { (**self).deallocate(pointer, layout) }
+    }
+}
+
+/// Allocates memory for the layout, zero-sized layouts get a dangling pointer instead.
+pub fn try_allocate<A: Allocator + ?Sized>(
+    allocator: &A,
+    layout: Layout,
+) -> Result<ptr::NonNull<u8>, AllocError> {
+    // Allocating zero bytes is undefined behaviour, so return a well-aligned dangling pointer
+    if layout.size() == 0 {
+        // Safety: The alignment of a layout is never 0
+        return Ok(unsafe {
+            ptr::NonNull::new_unchecked(ptr::without_provenance_mut(layout.align()))
+        });
+    }
+
+    allocator.allocate(layout)
+}
+
+/// Allocates zeroed memory for the layout, zero-sized layouts get a dangling pointer instead.
+pub fn try_allocate_zeroed<A: Allocator + ?Sized>(
+    allocator: &A,
+    layout: Layout,
+) -> Result<ptr::NonNull<u8>, AllocError> {
+    // Allocating zero bytes is undefined behaviour, so return a well-aligned dangling pointer
+    if layout.size() == 0 {
+        // Safety: The alignment of a layout is never 0
+        return Ok(unsafe {
+            ptr::NonNull::new_unchecked(ptr::without_provenance_mut(layout.align()))
+        });
+    }
+
+    allocator.allocate_zeroed(layout)
+}
+
+/// Allocates memory for the layout, calls the allocation error handler if that fails.
+pub fn allocate<A: Allocator + ?Sized>(allocator: &A, layout: Layout) -> ptr::NonNull<u8> {
+    try_allocate(allocator, layout)
+        .unwrap_or_else(|AllocError| alloc::alloc::handle_alloc_error(layout))
+}
+
+/// Allocates zeroed memory for the layout, calls the allocation error handler if that fails.
+pub fn allocate_zeroed<A: Allocator + ?Sized>(allocator: &A, layout: Layout) -> ptr::NonNull<u8> {
+    try_allocate_zeroed(allocator, layout)
+        .unwrap_or_else(|AllocError| alloc::alloc::handle_alloc_error(layout))
+}
+
+/// Frees memory allocated by `allocate` or `allocate_zeroed`, dangling pointers for zero-sized
+/// layouts are ignored.
+///
+/// # Safety
+/// The pointer has to be allocated by `allocate` or `allocate_zeroed` with the same allocator
+/// and layout.
+pub unsafe fn deallocate<A: Allocator + ?Sized>(
+    allocator: &A,
+    pointer: ptr::NonNull<u8>,
+    layout: Layout,
+) {
+    // Nothing was allocated for zero-sized layouts
+    if layout.size() != 0 {
+        // Safety: The pointer was allocated with the same layout
+        unsafe { allocator.deallocate(pointer, layout) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::alloc::Layout;
+
+    use super::{deallocate, try_allocate, AllocError, Global};
+
+    #[test]
+    fn allocation() {
+        // Allocate memory for a value
+        let layout = Layout::new::<u64>();
+        let pointer = try_allocate(&Global, layout).unwrap();
+
+        // Check whether the memory is aligned
+        assert!(pointer.cast::<u64>().is_aligned());
+
+        // Free the memory again
+        // Safety: The memory was allocated with the same layout
+        unsafe { deallocate(&Global, pointer, layout) }
+    }
+
+    #[test]
+    fn allocation_failure() {
+        // Try to allocate more memory than any system has
+        let layout = Layout::from_size_align(isize::MAX.unsigned_abs() - 4096, 8).unwrap();
+
+        // Check whether the failure is returned
+        assert_eq!(try_allocate(&Global, layout), Err(AllocError));
+    }
+}
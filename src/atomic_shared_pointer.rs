@@ -0,0 +1,675 @@
+use core::{
+    fmt,
+    marker::PhantomData,
+    ptr,
+    sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering},
+};
+
+use crate::{shared_pointer::SharedPointer, unique_pointer::UniquePointer};
+
+/// Mask of the low bits of a stored pointer, which count the readers borrowing it.
+///
+/// The value of a `SharedPointer` is stored after its two counters, so its address is always
+/// aligned to an `AtomicUsize`, which leaves the low bits free.
+const BORROW_MASK: usize = align_of::<AtomicUsize>() - 1;
+
+/// Converts an optional `SharedPointer` to a pointer that can be stored, without any borrows.
+fn into_stored<T>(pointer: Option<SharedPointer<T>>) -> *mut T {
+    pointer.map_or(ptr::null_mut(), |shared| {
+        SharedPointer::into_raw(shared).cast_mut()
+    })
+}
+
+/// Converts a stored pointer without borrows back to the `SharedPointer` it owns.
+///
+/// # Safety
+/// The pointer has to be returned by `into_stored`, and its strong reference is taken over.
+unsafe fn from_stored<T>(pointer: *mut T) -> Option<SharedPointer<T>> {
+    // Safety: The caller guarantees that the pointer owns a strong reference
+    (!pointer.is_null()).then(|| unsafe { SharedPointer::from_raw(pointer) })
+}
+
+/// Splits a stored pointer into the pointer to the value and the number of borrows.
+fn split<T>(stored: *mut T) -> (*mut T, usize) {
+    (
+        stored.map_addr(|address| address & !BORROW_MASK),
+        stored.addr() & BORROW_MASK,
+    )
+}
+
+/// Marks a value announced in a `Hazard`, once a writer added a strong reference for it.
+const HANDED_OVER: usize = 1;
+
+/// Announces the value a reader is loading, when every borrow of the stored pointer is taken.
+///
+/// Hazards are only added to a slot, and freed together with it, so readers can claim them
+/// without any borrows.
+struct Hazard<T> {
+    /// Whether a reader is using the hazard.
+    claimed: AtomicBool,
+
+    /// The announced value, marked as `HANDED_OVER` by a writer that replaced it.
+    value: AtomicPtr<T>,
+
+    /// The hazard that was added before this one.
+    next: *mut Self,
+}
+
+/// An optional `SharedPointer` that can be loaded and replaced by multiple threads at once.
+///
+/// The stored pointer owns a strong reference. Readers borrow it by incrementing a count in the
+/// low bits of the pointer, before they increment the strong count, so a writer can't release the
+/// value in between. A writer that replaces a borrowed pointer adds a strong reference for every
+/// possible borrow first, which the borrowing readers release when they notice the replacement.
+///
+/// Only a few borrows fit into the low bits, 7 on 64-bit targets. A reader that finds all of them
+/// taken announces the value in a hazard of the slot instead, which a writer hands a strong
+/// reference to when it replaces the value, so no reader ever waits for another one.
+pub struct AtomicOptionSharedPointer<T>(
+    AtomicPtr<T>,
+    AtomicPtr<Hazard<T>>,
+    PhantomData<SharedPointer<T>>,
+);
+
+impl<T> AtomicOptionSharedPointer<T> {
+    /// Stores the `SharedPointer` in a new slot.
+    #[inline]
+    pub fn new(pointer: Option<SharedPointer<T>>) -> Self {
+        Self(
+            AtomicPtr::new(into_stored(pointer)),
+            AtomicPtr::new(ptr::null_mut()),
+            PhantomData,
+        )
+    }
+
+    /// Creates an empty slot, which can be used in a `static`.
+    #[inline]
+    pub const fn none() -> Self {
+        Self(
+            AtomicPtr::new(ptr::null_mut()),
+            AtomicPtr::new(ptr::null_mut()),
+            PhantomData,
+        )
+    }
+
+    /// Returns a new `SharedPointer` to the stored value.
+    #[inline]
+    pub fn load(&self) -> Option<SharedPointer<T>> {
+        // Safety: The loaded pointer owns the strong reference created for it
+        unsafe { from_stored(self.load_raw()) }
+    }
+
+    /// Stores a new `SharedPointer`, the previous one is dropped.
+    #[inline]
+    pub fn store(&self, pointer: Option<SharedPointer<T>>) {
+        drop(self.swap(pointer));
+    }
+
+    /// Stores a new `SharedPointer` and returns the previous one.
+    #[inline]
+    pub fn swap(&self, pointer: Option<SharedPointer<T>>) -> Option<SharedPointer<T>> {
+        // Safety: The previous pointer owns the strong reference of the slot
+        unsafe { from_stored(self.swap_raw(into_stored(pointer))) }
+    }
+
+    /// Removes the `SharedPointer` from the slot and returns it.
+    #[inline]
+    pub fn take(&self) -> Option<SharedPointer<T>> {
+        self.swap(None)
+    }
+
+    /// Stores the new `SharedPointer`, if the stored one points to the same value as `current`.
+    ///
+    /// # Errors
+    /// Returns the new `SharedPointer` if a different value is stored.
+    #[inline]
+    pub fn compare_exchange(
+        &self,
+        current: Option<&SharedPointer<T>>,
+        new: Option<SharedPointer<T>>,
+    ) -> Result<Option<SharedPointer<T>>, Option<SharedPointer<T>>> {
+        let expected = current.map_or(ptr::null_mut(), |shared| {
+            SharedPointer::as_ptr(shared).cast_mut()
+        });
+
+        // Safety: The current pointer keeps the expected value alive
+        let result = unsafe { self.compare_exchange_raw(expected, into_stored(new)) };
+        match result {
+            // Safety: The previous pointer owns the strong reference of the slot
+            Ok(previous) => Ok(unsafe { from_stored(previous) }),
+
+            // Safety: The new pointer wasn't stored, so it still owns its strong reference
+            Err(rejected) => Err(unsafe { from_stored(rejected) }),
+        }
+    }
+
+    /// Returns the stored `SharedPointer`.
+    #[inline]
+    pub fn into_inner(self) -> Option<SharedPointer<T>> {
+        self.take()
+    }
+
+    /// Returns a pointer owning a new strong reference to the stored value, or NULL if the slot is
+    /// empty.
+    fn load_raw(&self) -> *mut T {
+        // Borrow the stored pointer, so the value can't be released before its count is incremented
+        // Acquire synchronizes with the Release exchange that stored the pointer
+        let mut stored = self.0.load(Ordering::Acquire);
+        let (value, saturated) = loop {
+            let (value, borrows) = split(stored);
+            if value.is_null() {
+                return value;
+            }
+
+            if borrows == BORROW_MASK {
+                // Every borrow is taken, announce the value instead of waiting for a borrow
+                return self.load_announced();
+            }
+
+            match self.0.compare_exchange_weak(
+                stored,
+                stored.map_addr(|address| address.wrapping_add(1)),
+                Ordering::Acquire,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => break (value, borrows.wrapping_add(1) == BORROW_MASK),
+                Err(current) => stored = current,
+            }
+        };
+
+        // Safety: The borrow keeps a strong reference to the value alive, either the one of this
+        // slot or the one added by the writer that replaced it
+        unsafe { SharedPointer::increment_strong_count(value) }
+        if saturated {
+            // Safety: The new strong reference keeps the value alive
+            unsafe { self.reset_borrows(value) }
+        }
+        self.release_borrow(value);
+        value
+    }
+
+    /// Returns a pointer owning a new strong reference to the stored value, or NULL if the slot is
+    /// empty, by announcing the value in a hazard instead of borrowing the pointer.
+    fn load_announced(&self) -> *mut T {
+        let hazard = self.claim_hazard();
+        let value = loop {
+            let (value, _) = split(self.0.load(Ordering::SeqCst));
+            if value.is_null() {
+                break value;
+            }
+
+            // A writer that replaces the value after the announcement finds it in the hazard
+            hazard.value.store(value, Ordering::SeqCst);
+            let announced = split(self.0.load(Ordering::SeqCst)).0 == value;
+            if announced {
+                // Safety: The slot or the writer that replaced the value keeps a strong reference
+                // to it for the hazard
+                unsafe { SharedPointer::increment_strong_count(value) }
+            }
+            Self::withdraw(hazard);
+            if announced {
+                break value;
+            }
+        };
+
+        // Release makes sure the hazard is withdrawn before the next reader claims it
+        hazard.claimed.store(false, Ordering::Release);
+        value
+    }
+
+    /// Claims an unused hazard of the slot, or adds a new one if all of them are in use.
+    fn claim_hazard(&self) -> &Hazard<T> {
+        // Acquire makes sure the hazards are initialized before they are used
+        let mut current = self.1.load(Ordering::Acquire);
+
+        // Safety: Hazards are only freed together with the slot
+        while let Some(hazard) = unsafe { current.as_ref() } {
+            if hazard
+                .claimed
+                .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+            {
+                return hazard;
+            }
+            current = hazard.next;
+        }
+
+        let new = UniquePointer::into_raw(UniquePointer::new(Hazard {
+            claimed: AtomicBool::new(true),
+            value: AtomicPtr::new(ptr::null_mut()),
+            next: ptr::null_mut(),
+        }));
+
+        // SeqCst makes sure a writer that replaces the announced value finds the new hazard
+        let mut next = self.1.load(Ordering::Relaxed);
+        loop {
+            // Safety: The hazard isn't shared yet
+            unsafe {
+                (*new).next = next;
+            }
+            match self
+                .1
+                .compare_exchange_weak(next, new, Ordering::SeqCst, Ordering::Relaxed)
+            {
+                Ok(_) => break,
+                Err(now) => next = now,
+            }
+        }
+
+        // Safety: Hazards are only freed together with the slot
+        unsafe { &*new }
+    }
+
+    /// Removes the value from the hazard, and releases the strong reference a writer handed over
+    /// for it.
+    fn withdraw(hazard: &Hazard<T>) {
+        // Acquire makes sure the handed over reference is counted before it's released
+        let withdrawn = hazard.value.swap(ptr::null_mut(), Ordering::Acquire);
+        if withdrawn.addr() & HANDED_OVER != 0 {
+            // Safety: The writer added a strong reference for the hazard
+            unsafe {
+                SharedPointer::decrement_strong_count(
+                    withdrawn.map_addr(|address| address & !HANDED_OVER),
+                );
+            }
+        }
+    }
+
+    /// Hands a strong reference to every reader that announced the replaced value in a hazard.
+    ///
+    /// # Safety
+    /// The replaced value has to be kept alive by another strong reference.
+    unsafe fn hand_over(&self, replaced: *mut T) {
+        // SeqCst makes sure every hazard announced before the replacement is found
+        let mut current = self.1.load(Ordering::SeqCst);
+
+        // Safety: Hazards are only freed together with the slot
+        while let Some(hazard) = unsafe { current.as_ref() } {
+            if hazard.value.load(Ordering::SeqCst) == replaced {
+                // Safety: The caller guarantees that the value is alive
+                unsafe { SharedPointer::increment_strong_count(replaced) }
+
+                // Release makes sure the reference is counted before the reader releases it
+                let marked = replaced.map_addr(|address| address | HANDED_OVER);
+                if hazard
+                    .value
+                    .compare_exchange(replaced, marked, Ordering::Release, Ordering::Relaxed)
+                    .is_err()
+                {
+                    // Safety: The reader withdrew the value, so the reference isn't needed
+                    unsafe { SharedPointer::decrement_strong_count(replaced) }
+                }
+            }
+            current = hazard.next;
+        }
+    }
+
+    /// Stores the pointer to the value again without borrows, so other readers don't have to
+    /// announce the value until the borrowing readers are done.
+    ///
+    /// # Safety
+    /// The value has to be kept alive by another strong reference.
+    unsafe fn reset_borrows(&self, value: *mut T) {
+        // Safety: The caller guarantees that the value is alive, the reference is owned by the
+        // stored pointer
+        unsafe { SharedPointer::increment_strong_count(value) }
+
+        // The borrows are replaced by strong references, like for a writer
+        // Safety: The caller guarantees that the value is alive
+        let (Ok(released) | Err(released)) = unsafe { self.compare_exchange_raw(value, value) };
+
+        // Safety: Either the replaced pointer or the rejected one owns a strong reference
+        unsafe { SharedPointer::decrement_strong_count(released) }
+    }
+
+    /// Returns a borrow of the pointer to the value, or releases the strong reference added for it
+    /// if the pointer was replaced.
+    fn release_borrow(&self, value: *mut T) {
+        // Acquire makes sure the references added by a writer are counted before one is released
+        let mut stored = self.0.load(Ordering::Acquire);
+        loop {
+            let (current, borrows) = split(stored);
+
+            // The borrow may also have been replaced, if the same value was stored again
+            if current != value || borrows == 0 {
+                // Safety: The writer that replaced the pointer added a strong reference for the
+                // borrow, the increment of this reader keeps the value alive
+                unsafe { SharedPointer::decrement_strong_count(value) }
+                return;
+            }
+
+            // Release makes sure the increment happens before a writer replaces the pointer
+            match self.0.compare_exchange_weak(
+                stored,
+                stored.map_addr(|address| address.wrapping_sub(1)),
+                Ordering::Release,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return,
+                Err(now) => stored = now,
+            }
+        }
+    }
+
+    /// Stores a pointer returned by `into_stored`, and returns the previous one.
+    fn swap_raw(&self, new: *mut T) -> *mut T {
+        loop {
+            // The loaded pointer keeps the value alive while it's replaced
+            let current = self.load_raw();
+
+            // Safety: The loaded pointer keeps the current value alive
+            let result = unsafe { self.compare_exchange_raw(current, new) };
+
+            // Safety: The loaded pointer owns the strong reference created for it
+            drop(unsafe { from_stored(current) });
+            if let Ok(previous) = result {
+                return previous;
+            }
+        }
+    }
+
+    /// Stores a pointer returned by `into_stored`, if the stored pointer points to the expected
+    /// value, returns the previous pointer or the rejected one.
+    ///
+    /// # Safety
+    /// The expected value has to be kept alive by another strong reference.
+    unsafe fn compare_exchange_raw(&self, expected: *mut T, new: *mut T) -> Result<*mut T, *mut T> {
+        // Add a strong reference for every reader that may be borrowing the pointer, before it's
+        // replaced, as the readers release them as soon as they see the replacement
+        if !expected.is_null() {
+            for _ in 0..BORROW_MASK {
+                // Safety: The caller guarantees that the value is alive
+                unsafe { SharedPointer::increment_strong_count(expected) }
+            }
+        }
+
+        // Replace the pointer, as long as it points to the expected value
+        // Release publishes the new value and the added references, Acquire makes sure the
+        // increments of readers that returned their borrow happen before the old value is returned,
+        // SeqCst makes sure hazards announced before the replacement are found afterwards
+        let mut stored = self.0.load(Ordering::Relaxed);
+        let result = loop {
+            let (value, borrows) = split(stored);
+            if value != expected {
+                break Err(new);
+            }
+
+            match self
+                .0
+                .compare_exchange_weak(stored, new, Ordering::SeqCst, Ordering::Relaxed)
+            {
+                Ok(_) => break Ok(borrows),
+                Err(now) => stored = now,
+            }
+        };
+
+        // Release the references that weren't needed for borrows
+        if !expected.is_null() {
+            for _ in result.unwrap_or(0)..BORROW_MASK {
+                // Safety: The caller guarantees that the value is alive
+                unsafe { SharedPointer::decrement_strong_count(expected) }
+            }
+
+            if result.is_ok() {
+                // Safety: The caller guarantees that the value is alive
+                unsafe { self.hand_over(expected) }
+            }
+        }
+        result.map(|_| expected)
+    }
+}
+
+impl<T> Default for AtomicOptionSharedPointer<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl<T> From<Option<SharedPointer<T>>> for AtomicOptionSharedPointer<T> {
+    #[inline]
+    fn from(pointer: Option<SharedPointer<T>>) -> Self {
+        Self::new(pointer)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for AtomicOptionSharedPointer<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_fmt(format_args!("AtomicOptionSharedPointer({:?})", self.load()))
+    }
+}
+
+impl<T> Drop for AtomicOptionSharedPointer<T> {
+    #[inline]
+    fn drop(&mut self) {
+        // No reader can be borrowing the pointer anymore
+        let (value, _) = split(*self.0.get_mut());
+
+        // Safety: The stored pointer owns a strong reference
+        drop(unsafe { from_stored(value) });
+
+        // No reader can be using the hazards anymore
+        let mut current = *self.1.get_mut();
+        while !current.is_null() {
+            // Safety: The hazard was created by `claim_hazard`, and is only freed here
+            let hazard = unsafe { UniquePointer::from_raw(current) };
+            current = hazard.next;
+        }
+    }
+}
+
+/// A `SharedPointer` that can be loaded and replaced by multiple threads at once.
+pub struct AtomicSharedPointer<T>(AtomicOptionSharedPointer<T>);
+
+impl<T> AtomicSharedPointer<T> {
+    /// Stores the `SharedPointer` in a new slot.
+    #[inline]
+    pub fn new(pointer: SharedPointer<T>) -> Self {
+        Self(AtomicOptionSharedPointer::new(Some(pointer)))
+    }
+
+    /// Returns a new `SharedPointer` to the stored value.
+    #[inline]
+    pub fn load(&self) -> SharedPointer<T> {
+        // Safety: The slot is never empty, and the loaded pointer owns a new strong reference
+        unsafe { SharedPointer::from_raw(self.0.load_raw()) }
+    }
+
+    /// Stores a new `SharedPointer`, the previous one is dropped.
+    #[inline]
+    pub fn store(&self, pointer: SharedPointer<T>) {
+        drop(self.swap(pointer));
+    }
+
+    /// Stores a new `SharedPointer` and returns the previous one.
+    #[inline]
+    pub fn swap(&self, pointer: SharedPointer<T>) -> SharedPointer<T> {
+        // Safety: The slot is never empty, and the previous pointer owns the strong reference of
+        // the slot
+        unsafe { SharedPointer::from_raw(self.0.swap_raw(into_stored(Some(pointer)))) }
+    }
+
+    /// Stores the new `SharedPointer`, if the stored one points to the same value as `current`.
+    ///
+    /// # Errors
+    /// Returns the new `SharedPointer` if a different value is stored.
+    #[inline]
+    pub fn compare_exchange(
+        &self,
+        current: &SharedPointer<T>,
+        new: SharedPointer<T>,
+    ) -> Result<SharedPointer<T>, SharedPointer<T>> {
+        let expected = SharedPointer::as_ptr(current).cast_mut();
+
+        // Safety: The current pointer keeps the expected value alive
+        let result = unsafe {
+            self.0
+                .compare_exchange_raw(expected, into_stored(Some(new)))
+        };
+        match result {
+            // Safety: The previous pointer owns the strong reference of the slot
+            Ok(previous) => Ok(unsafe { SharedPointer::from_raw(previous) }),
+
+            // Safety: The new pointer wasn't stored, so it still owns its strong reference
+            Err(rejected) => Err(unsafe { SharedPointer::from_raw(rejected) }),
+        }
+    }
+
+    /// Returns the stored `SharedPointer`.
+    #[inline]
+    pub fn into_inner(self) -> SharedPointer<T> {
+        // Safety: The slot is never empty, and the pointer owns the strong reference of the slot
+        unsafe { SharedPointer::from_raw(self.0.swap_raw(ptr::null_mut())) }
+    }
+}
+
+impl<T: Default> Default for AtomicSharedPointer<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(SharedPointer::default())
+    }
+}
+
+impl<T> From<SharedPointer<T>> for AtomicSharedPointer<T> {
+    #[inline]
+    fn from(pointer: SharedPointer<T>) -> Self {
+        Self::new(pointer)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for AtomicSharedPointer<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_fmt(format_args!("AtomicSharedPointer({:?})", self.load()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::Ordering;
+
+    use super::{split, AtomicOptionSharedPointer, BORROW_MASK, HANDED_OVER};
+    use crate::SharedPointer;
+
+    #[test]
+    fn borrows_are_returned() {
+        // Store a value in a slot
+        let pointer = SharedPointer::new(rand::random::<u64>());
+        let slot = AtomicOptionSharedPointer::new(Some(pointer.clone()));
+
+        // Loading it should leave no borrows behind
+        let loaded = slot.load().unwrap();
+        assert_eq!(split(slot.0.load(Ordering::Relaxed)).1, 0);
+        assert_eq!(pointer.reference_count(), 3);
+
+        // Replacing it should only leave the references of the pointers behind
+        drop(slot.compare_exchange(Some(&loaded), None).unwrap());
+        assert_eq!(pointer.reference_count(), 2);
+    }
+
+    #[test]
+    fn replaced_borrow() {
+        // Store a value in a slot, and borrow it like a reader that didn't increment the count yet
+        let pointer = SharedPointer::new(rand::random::<u64>());
+        let slot = AtomicOptionSharedPointer::new(Some(pointer.clone()));
+        let stored = slot.0.load(Ordering::Relaxed);
+        slot.0
+            .store(stored.map_addr(|address| address + 1), Ordering::Relaxed);
+
+        // Replacing the pointer keeps a strong reference for the borrow
+        let previous = slot.compare_exchange(Some(&pointer), None).unwrap();
+        assert_eq!(pointer.reference_count(), 3);
+
+        // The reader releases it after incrementing the count
+        let value = split(stored).0;
+        // Safety: The value is kept alive by the pointers
+        unsafe { SharedPointer::increment_strong_count(value) }
+        slot.release_borrow(value);
+        assert_eq!(pointer.reference_count(), 3);
+
+        // Safety: The reader owns the strong reference it created
+        drop(unsafe { SharedPointer::from_raw(value) });
+        drop(previous);
+        assert_eq!(pointer.reference_count(), 1);
+    }
+
+    #[test]
+    fn saturated_borrows() {
+        // Store a value in a slot, and borrow it like readers that didn't increment the count yet
+        let pointer = SharedPointer::new(rand::random::<u64>());
+        let slot = AtomicOptionSharedPointer::new(Some(pointer.clone()));
+        let stored = slot.0.load(Ordering::Relaxed);
+        slot.0.store(
+            stored.map_addr(|address| address + BORROW_MASK - 1),
+            Ordering::Relaxed,
+        );
+
+        // The reader that takes the last borrow stores the pointer again without borrows
+        let loaded = slot.load().unwrap();
+        assert_eq!(split(slot.0.load(Ordering::Relaxed)).1, 0);
+        assert_eq!(pointer.reference_count(), 3 + BORROW_MASK - 1);
+
+        // The other readers release the strong references added for them
+        let value = split(stored).0;
+        for _ in 1..BORROW_MASK {
+            // Safety: The value is kept alive by the pointers
+            unsafe { SharedPointer::increment_strong_count(value) }
+            slot.release_borrow(value);
+
+            // Safety: The reader owns the strong reference it created
+            drop(unsafe { SharedPointer::from_raw(value) });
+        }
+        assert_eq!(pointer.reference_count(), 3);
+        drop(loaded);
+        assert_eq!(pointer.reference_count(), 2);
+    }
+    #[test]
+    fn announced_load() {
+        // Store a value in a slot, and take every borrow like readers that didn't finish yet
+        let pointer = SharedPointer::new(rand::random::<u64>());
+        let slot = AtomicOptionSharedPointer::new(Some(pointer.clone()));
+        let stored = slot.0.load(Ordering::Relaxed);
+        slot.0.store(
+            stored.map_addr(|address| address + BORROW_MASK),
+            Ordering::Relaxed,
+        );
+
+        // Another reader announces the value in a hazard, without waiting for a borrow
+        let loaded = slot.load().unwrap();
+        assert!(SharedPointer::ptr_eq(&loaded, &pointer));
+        assert_eq!(split(slot.0.load(Ordering::Relaxed)).1, BORROW_MASK);
+        assert_eq!(pointer.reference_count(), 3);
+
+        // The hazard is withdrawn and can be claimed again
+        let hazard = slot.claim_hazard();
+        assert!(hazard.value.load(Ordering::Relaxed).is_null());
+        assert!(hazard.next.is_null());
+        slot.0.store(stored, Ordering::Relaxed);
+    }
+
+    #[test]
+    fn handed_over_hazard() {
+        // Store a value in a slot, and announce it like a reader that didn't increment the count
+        // yet
+        let pointer = SharedPointer::new(rand::random::<u64>());
+        let slot = AtomicOptionSharedPointer::new(Some(pointer.clone()));
+        let value = split(slot.0.load(Ordering::Relaxed)).0;
+        let hazard = slot.claim_hazard();
+        hazard.value.store(value, Ordering::Relaxed);
+
+        // Replacing the value hands a strong reference over to the hazard
+        let previous = slot.swap(None).unwrap();
+        assert_eq!(
+            hazard.value.load(Ordering::Relaxed).addr(),
+            value.addr() | HANDED_OVER
+        );
+        assert_eq!(pointer.reference_count(), 3);
+
+        // The reader releases it when it withdraws the value
+        AtomicOptionSharedPointer::withdraw(hazard);
+        assert_eq!(pointer.reference_count(), 2);
+        drop(previous);
+        assert_eq!(pointer.reference_count(), 1);
+    }
+}
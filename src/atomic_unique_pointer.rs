@@ -0,0 +1,144 @@
+use core::{
+    fmt,
+    marker::PhantomData,
+    ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+use crate::unique_pointer::UniquePointer;
+
+/// Converts an optional `UniquePointer` to a pointer that can be stored.
+fn into_stored<T>(pointer: Option<UniquePointer<T>>) -> *mut T {
+    pointer.map_or(ptr::null_mut(), UniquePointer::into_raw)
+}
+
+/// Converts a stored pointer back to the `UniquePointer` owning the value.
+///
+/// # Safety
+/// The pointer has to be returned by `into_stored`, and the value it owns is taken over.
+unsafe fn from_stored<T>(pointer: *mut T) -> Option<UniquePointer<T>> {
+    // Safety: The caller guarantees that the pointer owns the value
+    (!pointer.is_null()).then(|| unsafe { UniquePointer::from_raw(pointer) })
+}
+
+/// A slot for an optional `UniquePointer`, which threads can hand the value over through.
+///
+/// Every operation moves the ownership of the value in or out of the slot, so the value can't be
+/// accessed while it's stored.
+pub struct AtomicUniquePointer<T>(AtomicPtr<T>, PhantomData<UniquePointer<T>>);
+
+/// Safety: The value is only moved between threads, never shared.
+unsafe impl<T: Send> Send for AtomicUniquePointer<T> {}
+
+/// Safety: The value is only moved between threads, never shared.
+unsafe impl<T: Send> Sync for AtomicUniquePointer<T> {}
+
+impl<T> AtomicUniquePointer<T> {
+    /// Stores the `UniquePointer` in a new slot.
+    #[inline]
+    pub fn new(pointer: Option<UniquePointer<T>>) -> Self {
+        Self(AtomicPtr::new(into_stored(pointer)), PhantomData)
+    }
+
+    /// Creates an empty slot, which can be used in a `static`.
+    #[inline]
+    pub const fn none() -> Self {
+        Self(AtomicPtr::new(ptr::null_mut()), PhantomData)
+    }
+
+    /// Stores a new `UniquePointer`, the previous one is dropped.
+    #[inline]
+    pub fn store(&self, pointer: Option<UniquePointer<T>>) {
+        drop(self.swap(pointer));
+    }
+
+    /// Stores a new `UniquePointer` and returns the previous one.
+    #[inline]
+    pub fn swap(&self, pointer: Option<UniquePointer<T>>) -> Option<UniquePointer<T>> {
+        // Release hands the new value over, Acquire takes over the previous one
+        let previous = self.0.swap(into_stored(pointer), Ordering::AcqRel);
+
+        // Safety: The previous pointer was stored by this slot, which owned its value
+        unsafe { from_stored(previous) }
+    }
+
+    /// Removes the `UniquePointer` from the slot and returns it.
+    #[inline]
+    pub fn take(&self) -> Option<UniquePointer<T>> {
+        self.swap(None)
+    }
+
+    /// Stores the new `UniquePointer`, if the stored one points to `current`, which is NULL for an
+    /// empty slot.
+    ///
+    /// # Errors
+    /// Returns the new `UniquePointer` if a different value is stored.
+    #[inline]
+    pub fn compare_exchange(
+        &self,
+        current: *const T,
+        new: Option<UniquePointer<T>>,
+    ) -> Result<Option<UniquePointer<T>>, Option<UniquePointer<T>>> {
+        // Release hands the new value over, Acquire takes over the previous one
+        let new_stored = into_stored(new);
+        let result = self.0.compare_exchange(
+            current.cast_mut(),
+            new_stored,
+            Ordering::AcqRel,
+            Ordering::Relaxed,
+        );
+
+        // Safety: The previous pointer was stored by this slot, which owned its value. If the
+        // exchange failed, the new pointer wasn't stored, so it still owns its value.
+        let pointer = unsafe { from_stored(result.unwrap_or(new_stored)) };
+        if result.is_ok() {
+            Ok(pointer)
+        } else {
+            Err(pointer)
+        }
+    }
+
+    /// Returns a mutable reference to the stored value, the slot is borrowed so no other thread
+    /// can access it.
+    #[inline]
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        // Safety: The stored pointer owns the value, and the reference borrows the slot
+        unsafe { self.0.get_mut().as_mut() }
+    }
+
+    /// Returns the stored `UniquePointer`.
+    #[inline]
+    pub fn into_inner(self) -> Option<UniquePointer<T>> {
+        self.take()
+    }
+}
+
+impl<T> Default for AtomicUniquePointer<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl<T> From<UniquePointer<T>> for AtomicUniquePointer<T> {
+    #[inline]
+    fn from(pointer: UniquePointer<T>) -> Self {
+        Self::new(Some(pointer))
+    }
+}
+
+impl<T> fmt::Debug for AtomicUniquePointer<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The value is owned by the slot, so only its address can be shown
+        f.write_fmt(format_args!("AtomicUniquePointer({:?})", self.0))
+    }
+}
+
+impl<T> Drop for AtomicUniquePointer<T> {
+    #[inline]
+    fn drop(&mut self) {
+        // Safety: The stored pointer owns the value
+        drop(unsafe { from_stored(*self.0.get_mut()) });
+    }
+}
@@ -0,0 +1,370 @@
+//! Checks for the auto traits, variance, drop-check and lifetime behaviour of the pointer types.
+//!
+//! These doctests only exist to catch regressions, they are compiled by `cargo test`.
+//!
+//! A `UniquePointer` is `Send` and `Sync` if the value is:
+//! ```
+//! use smart_pointers::UniquePointer;
+//!
+//! fn is_send_sync<T: Send + Sync>() {}
+//! is_send_sync::<UniquePointer<i32>>();
+//! is_send_sync::<UniquePointer<[u8]>>();
+//! is_send_sync::<UniquePointer<dyn Send + Sync>>();
+//! ```
+//!
+//! A `UniquePointer` to a value that isn't `Send` can't be sent to another thread:
+//! ```compile_fail,E0277
+//! use std::rc::Rc;
+//! use smart_pointers::UniquePointer;
+//!
+//! fn is_send<T: Send>() {}
+//! is_send::<UniquePointer<Rc<i32>>>();
+//! ```
+//!
+//! A `UniquePointer` to a value that isn't `Sync` can't be shared between threads:
+//! ```compile_fail,E0277
+//! use std::cell::Cell;
+//! use smart_pointers::UniquePointer;
+//!
+//! fn is_sync<T: Sync>() {}
+//! is_sync::<UniquePointer<Cell<i32>>>();
+//! ```
+//!
+//! A `SharedPointer` and `WeakPointer` are `Send` and `Sync` if the value is both:
+//! ```
+//! use smart_pointers::{SharedPointer, WeakPointer};
+//!
+//! fn is_send_sync<T: Send + Sync>() {}
+//! is_send_sync::<SharedPointer<i32>>();
+//! is_send_sync::<SharedPointer<str>>();
+//! is_send_sync::<WeakPointer<i32>>();
+//! ```
+//!
+//! A `SharedPointer` to a value that isn't `Sync` can't be sent to another thread, as it would
+//! give multiple threads access to the value:
+//! ```compile_fail,E0277
+//! use std::cell::Cell;
+//! use smart_pointers::SharedPointer;
+//!
+//! fn is_send<T: Send>() {}
+//! is_send::<SharedPointer<Cell<i32>>>();
+//! ```
+//!
+//! A `SharedPointer` to a value that isn't `Send` can't be sent to another thread, as that thread
+//! may drop the value:
+//! ```compile_fail,E0277
+//! use std::sync::MutexGuard;
+//! use smart_pointers::SharedPointer;
+//!
+//! fn is_send<T: Send>() {}
+//! is_send::<SharedPointer<MutexGuard<'static, i32>>>();
+//! ```
+//!
+//! The same goes for a `WeakPointer`, as it can be upgraded:
+//! ```compile_fail,E0277
+//! use std::cell::Cell;
+//! use smart_pointers::WeakPointer;
+//!
+//! fn is_send<T: Send>() {}
+//! is_send::<WeakPointer<Cell<i32>>>();
+//! ```
+//!
+//! A `LocalSharedPointer` can't be sent to another thread, as its counters aren't atomic:
+//! ```compile_fail,E0277
+//! use smart_pointers::LocalSharedPointer;
+//!
+//! fn is_send<T: Send>() {}
+//! is_send::<LocalSharedPointer<i32>>();
+//! ```
+//!
+//! Neither can it be shared between threads:
+//! ```compile_fail,E0277
+//! use smart_pointers::LocalSharedPointer;
+//!
+//! fn is_sync<T: Sync>() {}
+//! is_sync::<LocalSharedPointer<i32>>();
+//! ```
+//!
+//! The same goes for a `LocalWeakPointer`:
+//! ```compile_fail,E0277
+//! use smart_pointers::LocalWeakPointer;
+//!
+//! fn is_send<T: Send>() {}
+//! is_send::<LocalWeakPointer<i32>>();
+//! ```
+//!
+//! A `CountedPointer` with any other counter that is `Sync` can be shared like a `SharedPointer`:
+//! ```
+//! use smart_pointers::{AtomicCounter32, CountedPointer, Global, SaturatingCounter};
+//!
+//! fn is_send_sync<T: Send + Sync>() {}
+//! is_send_sync::<CountedPointer<i32, Global, AtomicCounter32>>();
+//! is_send_sync::<CountedPointer<str, Global, SaturatingCounter>>();
+//! ```
+//!
+//! An `AtomicSharedPointer` can be shared between threads like the `SharedPointer` it stores:
+//! ```
+//! use smart_pointers::{AtomicOptionSharedPointer, AtomicSharedPointer};
+//!
+//! fn is_send_sync<T: Send + Sync>() {}
+//! is_send_sync::<AtomicSharedPointer<i32>>();
+//! is_send_sync::<AtomicOptionSharedPointer<i32>>();
+//! ```
+//!
+//! But only if the value could be shared by the `SharedPointer` as well:
+//! ```compile_fail,E0277
+//! use std::cell::Cell;
+//! use smart_pointers::AtomicSharedPointer;
+//!
+//! fn is_sync<T: Sync>() {}
+//! is_sync::<AtomicSharedPointer<Cell<i32>>>();
+//! ```
+//!
+//! A `UniqueSharedPointer` can be sent to another thread like a `UniquePointer`, as no other
+//! pointer to the value exists yet:
+//! ```
+//! use std::cell::Cell;
+//! use smart_pointers::{UniqueLocalSharedPointer, UniqueSharedPointer};
+//!
+//! fn is_send<T: Send>() {}
+//! is_send::<UniqueSharedPointer<Cell<i32>>>();
+//! is_send::<UniqueLocalSharedPointer<i32>>();
+//! ```
+//!
+//! But once it's shareable, the value has to be `Sync` again:
+//! ```compile_fail,E0277
+//! use std::cell::Cell;
+//! use smart_pointers::UniqueSharedPointer;
+//!
+//! fn send<T: Send>(_: T) {}
+//! send(UniqueSharedPointer::shareable(UniqueSharedPointer::new(Cell::new(5))));
+//! ```
+//!
+//! A `UniqueSharedPointer` isn't `UnwindSafe` if the value may be broken by a panic, as it gives
+//! mutable access:
+//! ```compile_fail,E0277
+//! use std::panic::UnwindSafe;
+//! use smart_pointers::UniqueSharedPointer;
+//!
+//! fn is_unwind_safe<T: UnwindSafe>() {}
+//! is_unwind_safe::<UniqueSharedPointer<&mut i32>>();
+//! ```
+//!
+//! A `MappedPointer` is `Send` and `Sync` like the `SharedPointer` it was mapped from:
+//! ```
+//! use smart_pointers::MappedPointer;
+//!
+//! fn is_send_sync<T: Send + Sync>() {}
+//! is_send_sync::<MappedPointer<(i32, String), String>>();
+//! ```
+//!
+//! The projection has to borrow from the value, not from something that may be dropped earlier:
+//! ```compile_fail,E0597
+//! use smart_pointers::SharedPointer;
+//!
+//! let pointer = SharedPointer::new(5);
+//! let local = 6;
+//! let mapped = SharedPointer::map(pointer, |_| &local);
+//! ```
+//!
+//! An `AtomicUniquePointer` only moves the value between threads, so it's `Sync` if the value is
+//! `Send`:
+//! ```
+//! use std::cell::Cell;
+//! use smart_pointers::AtomicUniquePointer;
+//!
+//! fn is_send_sync<T: Send + Sync>() {}
+//! is_send_sync::<AtomicUniquePointer<Cell<i32>>>();
+//! ```
+//!
+//! But a value that can't be sent can't be handed over:
+//! ```compile_fail,E0277
+//! use std::rc::Rc;
+//! use smart_pointers::AtomicUniquePointer;
+//!
+//! fn is_sync<T: Sync>() {}
+//! is_sync::<AtomicUniquePointer<Rc<i32>>>();
+//! ```
+//!
+//! Both pointers are `Unpin`, even if the value isn't:
+//! ```
+//! use std::marker::PhantomPinned;
+//! use smart_pointers::{SharedPointer, UniquePointer};
+//!
+//! fn is_unpin<T: Unpin>() {}
+//! is_unpin::<UniquePointer<PhantomPinned>>();
+//! is_unpin::<SharedPointer<PhantomPinned>>();
+//! ```
+//!
+//! But a pinned value that isn't `Unpin` can't be moved out of a pinned pointer:
+//! ```compile_fail,E0277
+//! use std::marker::PhantomPinned;
+//! use smart_pointers::UniquePointer;
+//!
+//! let mut pointer = UniquePointer::pin(PhantomPinned);
+//! let value = std::mem::replace(pointer.as_mut().get_mut(), PhantomPinned);
+//! ```
+//!
+//! Both pointers are `UnwindSafe` if the value can be accessed safely after a panic:
+//! ```
+//! use std::panic::{RefUnwindSafe, UnwindSafe};
+//! use smart_pointers::{SharedPointer, UniquePointer};
+//!
+//! fn is_unwind_safe<T: UnwindSafe + RefUnwindSafe>() {}
+//! is_unwind_safe::<UniquePointer<i32>>();
+//! is_unwind_safe::<SharedPointer<i32>>();
+//! ```
+//!
+//! A `UniquePointer` owns the value like `Box`, so it's `UnwindSafe` if the value is, even if the
+//! value has interior mutability:
+//! ```
+//! use std::{cell::RefCell, panic::UnwindSafe};
+//! use smart_pointers::UniquePointer;
+//!
+//! fn is_unwind_safe<T: UnwindSafe>() {}
+//! is_unwind_safe::<UniquePointer<RefCell<i32>>>();
+//! ```
+//!
+//! But not if the value isn't `UnwindSafe` itself:
+//! ```compile_fail,E0277
+//! use std::panic::UnwindSafe;
+//! use smart_pointers::UniquePointer;
+//!
+//! fn is_unwind_safe<T: UnwindSafe>() {}
+//! is_unwind_safe::<UniquePointer<&mut i32>>();
+//! ```
+//!
+//! A `SharedPointer` to a value with interior mutability isn't, as the value may be broken by a
+//! panic and still be accessed through another pointer:
+//! ```compile_fail,E0277
+//! use std::{cell::RefCell, panic::UnwindSafe};
+//! use smart_pointers::SharedPointer;
+//!
+//! fn is_unwind_safe<T: UnwindSafe>() {}
+//! is_unwind_safe::<SharedPointer<RefCell<i32>>>();
+//! ```
+//!
+//! Both pointers are covariant, like references:
+//! ```
+//! use smart_pointers::{SharedPointer, UniquePointer, WeakPointer};
+//!
+//! fn shorten_unique<'a>(pointer: UniquePointer<&'static str>) -> UniquePointer<&'a str> {
+//!     pointer
+//! }
+//!
+//! fn shorten_shared<'a>(pointer: SharedPointer<&'static str>) -> SharedPointer<&'a str> {
+//!     pointer
+//! }
+//!
+//! fn shorten_weak<'a>(pointer: WeakPointer<&'static str>) -> WeakPointer<&'a str> {
+//!     pointer
+//! }
+//! ```
+//!
+//! A lifetime can't be extended through a pointer:
+//! ```compile_fail
+//! use smart_pointers::UniquePointer;
+//!
+//! fn extend<'a>(pointer: UniquePointer<&'a str>) -> UniquePointer<&'static str> {
+//!     pointer
+//! }
+//! ```
+//!
+//! The drop checker knows that dropping a `UniquePointer` drops the value, so the value can't
+//! borrow data that is dropped earlier:
+//! ```compile_fail,E0597
+//! use smart_pointers::UniquePointer;
+//!
+//! struct PrintOnDrop<'a>(&'a String);
+//!
+//! impl Drop for PrintOnDrop<'_> {
+//!     fn drop(&mut self) {
+//!         println!("{}", self.0);
+//!     }
+//! }
+//!
+//! let pointer;
+//! let string = String::from("dropped before the pointer");
+//! pointer = UniquePointer::new(PrintOnDrop(&string));
+//! ```
+//!
+//! The same goes for a `SharedPointer`:
+//! ```compile_fail,E0597
+//! use smart_pointers::SharedPointer;
+//!
+//! struct PrintOnDrop<'a>(&'a String);
+//!
+//! impl Drop for PrintOnDrop<'_> {
+//!     fn drop(&mut self) {
+//!         println!("{}", self.0);
+//!     }
+//! }
+//!
+//! let pointer;
+//! let string = String::from("dropped before the pointer");
+//! pointer = SharedPointer::new(PrintOnDrop(&string));
+//! ```
+//!
+//! A `SharedPointer` doesn't give mutable access to the value:
+//! ```compile_fail,E0594
+//! use smart_pointers::SharedPointer;
+//!
+//! let mut pointer = SharedPointer::new(5);
+//! *pointer = 6;
+//! ```
+//!
+//! An in-place initialization has to initialize its own slot, not the slot of another pointer:
+//! ```compile_fail
+//! use smart_pointers::UniquePointer;
+//!
+//! let pointer = UniquePointer::new_with(|outer| {
+//!     let inner = UniquePointer::new_with(|inner| {
+//!         drop(inner);
+//!         outer.write(5)
+//!     });
+//!     todo!()
+//! });
+//! ```
+//!
+//! A value initialized in place by `pin_with` can't be moved by swapping it with another one:
+//! ```compile_fail,E0596
+//! use std::marker::PhantomPinned;
+//! use smart_pointers::UniquePointer;
+//!
+//! let mut first = UniquePointer::pin_with(|slot| slot.write(PhantomPinned));
+//! let mut second = UniquePointer::pin_with(|slot| slot.write(PhantomPinned));
+//! std::mem::swap(&mut *first, &mut *second);
+//! ```
+//!
+//! The `unsize` macro can't be used to rebuild a `UniquePointer` from a forged pointer, as the
+//! rebuilder can only be taken from a real pointer in unsafe code:
+//! ```compile_fail,E0133
+//! use smart_pointers::{unsize, UniquePointer};
+//!
+//! struct Forged<R>(*mut u64, R);
+//!
+//! impl<R> Forged<R> {
+//!     fn __into_unsize_parts(self) -> (*mut u64, R) {
+//!         (self.0, self.1)
+//!     }
+//! }
+//!
+//! let (_, rebuilder) = UniquePointer::new(0_u64).__into_unsize_parts();
+//! let pointer: UniquePointer<u64> = unsize!(Forged(0x10 as *mut u64, rebuilder));
+//! ```
+//!
+//! The same holds for a `SharedPointer`, which would otherwise decrement a forged counter:
+//! ```compile_fail,E0133
+//! use smart_pointers::{unsize, SharedPointer};
+//!
+//! struct Forged<P, R>(*mut P, R);
+//!
+//! impl<P, R> Forged<P, R> {
+//!     fn __into_unsize_parts(self) -> (*mut P, R) {
+//!         (self.0, self.1)
+//!     }
+//! }
+//!
+//! let (raw, rebuilder) = SharedPointer::new(0_u64).__into_unsize_parts();
+//! let pointer: SharedPointer<u64> = unsize!(Forged(raw.wrapping_add(1), rebuilder));
+//! ```
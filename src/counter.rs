@@ -0,0 +1,486 @@
+use core::{
+    cell::Cell,
+    hint,
+    sync::atomic::{self, AtomicU32, AtomicUsize, Ordering},
+};
+
+/// A reference count, stored twice in every allocation of a `CountedPointer`: once for the strong
+/// references and once for the weak references.
+///
+/// A new count starts at 1, and the count is locked only by `lock_unique`.
+///
+/// # Safety
+/// The count has to be exact until it saturates, a saturated count may never reach 0 again.
+/// The memory orderings described by the methods are required for the pointers to be sound. If the
+/// counter is `Sync`, every method has to be atomic.
+pub unsafe trait Counter {
+    /// Name of the pointer type using this counter, shown by its `Debug` implementation.
+    const NAME: &'static str;
+
+    /// Name of the weak pointer type using this counter, shown by its `Debug` implementation.
+    const WEAK_NAME: &'static str;
+
+    /// Creates a count of 1.
+    fn one() -> Self;
+
+    /// Creates a count of 0, for a value that isn't initialized yet.
+    fn zero() -> Self;
+
+    /// Changes a count created by `zero` to 1, once the value is initialized.
+    ///
+    /// Everything before it happens before `increment_nonzero` succeeds.
+    fn initialize(&self);
+
+    /// Returns the count, a locked count is 1.
+    ///
+    /// Synchronizes with the release of references, like an `Acquire` load.
+    fn count(&self) -> usize;
+
+    /// Increments the count, for a reference created from an existing one, aborts if the count
+    /// becomes too high.
+    fn increment(&self);
+
+    /// Increments the count unless it's 0, returns whether it was incremented.
+    ///
+    /// Synchronizes with the release of references, like an `Acquire` load.
+    fn increment_nonzero(&self) -> bool;
+
+    /// Decrements the count, returns whether it reached 0.
+    ///
+    /// If it did, every use of the value through other references happens before this returns.
+    fn decrement(&self) -> bool;
+
+    /// Changes the count from 1 to 0, returns whether it was 1.
+    ///
+    /// Synchronizes with the release of references, like an `Acquire` load.
+    fn release_unique(&self) -> bool;
+
+    /// Locks the count if it's 1, returns whether it was locked.
+    ///
+    /// Synchronizes with the release of references, like an `Acquire` load.
+    fn lock_unique(&self) -> bool;
+
+    /// Unlocks a count locked by `lock_unique`, so it's 1 again.
+    ///
+    /// Everything before it happens before `increment_unlocked` returns.
+    fn unlock(&self);
+
+    /// Increments the count after waiting until it's unlocked, aborts if the count becomes too
+    /// high.
+    fn increment_unlocked(&self);
+}
+
+/// Aborts the process, by panicking while panicking as `core` can't abort directly.
+#[expect(
+    clippy::panic,
+    reason = "A panic during unwinding is the only way to abort in core"
+)]
+#[cold]
+pub fn abort() -> ! {
+    struct Abort;
+
+    impl Drop for Abort {
+        fn drop(&mut self) {
+            panic!("Reference count overflow");
+        }
+    }
+
+    // Panicking while the guard is alive, makes it panic during unwinding
+    let _abort = Abort;
+    panic!("Reference count overflow");
+}
+
+/// Converts a 32-bit count to a `usize`, the count is never high enough to not fit.
+fn widen(count: u32) -> usize {
+    usize::try_from(count).unwrap_or(usize::MAX)
+}
+
+/// Implements `Counter` for a wrapper around an atomic integer.
+macro_rules! atomic_counter {
+    ($counter:ident, $atomic:ident, $integer:ident, $to_usize:path, $name:expr, $weak_name:expr) => {
+        /// Safety: Every method is atomic and uses the orderings of `Arc`.
+        unsafe impl Counter for $counter {
+            const NAME: &'static str = $name;
+            const WEAK_NAME: &'static str = $weak_name;
+
+            #[inline]
+            fn one() -> Self {
+                Self($atomic::new(1))
+            }
+
+            #[inline]
+            fn zero() -> Self {
+                Self($atomic::new(0))
+            }
+
+            #[inline]
+            fn initialize(&self) {
+                self.0.store(1, Ordering::Release);
+            }
+
+            #[inline]
+            fn count(&self) -> usize {
+                match self.0.load(Ordering::Acquire) {
+                    // The count is only locked while it's 1
+                    Self::LOCKED => 1,
+                    count => $to_usize(count),
+                }
+            }
+
+            #[inline]
+            fn increment(&self) {
+                // Relaxed is enough, as new references can only be created from an existing one
+                // The count is checked afterwards, as forgetting pointers in a loop could otherwise
+                // wrap it
+                if self.0.fetch_add(1, Ordering::Relaxed) > Self::MAX {
+                    abort();
+                }
+            }
+
+            #[inline]
+            fn increment_nonzero(&self) -> bool {
+                // Increment the count, unless the value has already been dropped
+                let previous_count = self
+                    .0
+                    .fetch_update(Ordering::Acquire, Ordering::Relaxed, |count| {
+                        (count != 0).then(|| count.wrapping_add(1))
+                    })
+                    .ok();
+
+                // Abort if the count became too high
+                if previous_count.is_some_and(|count| count > Self::MAX) {
+                    abort();
+                }
+                previous_count.is_some()
+            }
+
+            #[inline]
+            fn decrement(&self) -> bool {
+                // Release makes sure every use of the value happens before it is dropped
+                if self.0.fetch_sub(1, Ordering::Release) == 1 {
+                    // Synchronize with the decrements of other references
+                    atomic::fence(Ordering::Acquire);
+                    true
+                } else {
+                    false
+                }
+            }
+
+            #[inline]
+            fn release_unique(&self) -> bool {
+                self.0
+                    .compare_exchange(1, 0, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+            }
+
+            #[inline]
+            fn lock_unique(&self) -> bool {
+                self.0
+                    .compare_exchange(1, Self::LOCKED, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+            }
+
+            #[inline]
+            fn unlock(&self) {
+                self.0.store(1, Ordering::Release);
+            }
+
+            #[inline]
+            fn increment_unlocked(&self) {
+                // Acquire synchronizes with the Release store unlocking the count
+                let mut count = self.0.load(Ordering::Relaxed);
+                loop {
+                    if count == Self::LOCKED {
+                        hint::spin_loop();
+                        count = self.0.load(Ordering::Relaxed);
+                    } else if count > Self::MAX {
+                        abort();
+                    } else {
+                        match self.0.compare_exchange_weak(
+                            count,
+                            count.wrapping_add(1),
+                            Ordering::Acquire,
+                            Ordering::Relaxed,
+                        ) {
+                            Ok(_) => break,
+                            Err(current) => count = current,
+                        }
+                    }
+                }
+            }
+        }
+
+        impl $counter {
+            /// Highest allowed count, far enough from overflowing that every thread can detect
+            /// it.
+            const MAX: $integer = $integer::MAX >> 1;
+
+            /// The count while it's locked by `lock_unique`.
+            const LOCKED: $integer = $integer::MAX;
+        }
+    };
+}
+
+/// An atomic counter, for pointers that can be shared between threads.
+#[derive(Debug)]
+pub struct AtomicCounter(AtomicUsize);
+
+atomic_counter!(
+    AtomicCounter,
+    AtomicUsize,
+    usize,
+    core::convert::identity,
+    "SharedPointer",
+    "WeakPointer"
+);
+
+/// A 32-bit atomic counter, which makes allocations smaller on 64-bit targets, but only allows
+/// about 2 billion references.
+#[derive(Debug)]
+pub struct AtomicCounter32(AtomicU32);
+
+atomic_counter!(
+    AtomicCounter32,
+    AtomicU32,
+    u32,
+    widen,
+    "CountedPointer",
+    "CountedWeakPointer"
+);
+
+/// A counter that isn't atomic, for pointers that stay on one thread.
+#[derive(Debug)]
+pub struct LocalCounter(Cell<usize>);
+
+/// Safety: The counter isn't `Sync`, so it's only accessed by one thread.
+unsafe impl Counter for LocalCounter {
+    const NAME: &'static str = "LocalSharedPointer";
+    const WEAK_NAME: &'static str = "LocalWeakPointer";
+
+    #[inline]
+    fn one() -> Self {
+        Self(Cell::new(1))
+    }
+
+    #[inline]
+    fn zero() -> Self {
+        Self(Cell::new(0))
+    }
+
+    #[inline]
+    fn initialize(&self) {
+        self.0.set(1);
+    }
+
+    #[inline]
+    fn count(&self) -> usize {
+        self.0.get()
+    }
+
+    #[inline]
+    fn increment(&self) {
+        // Nothing can happen on other threads, so the count can be checked before changing it
+        match self.0.get().checked_add(1) {
+            Some(count) => self.0.set(count),
+            None => abort(),
+        }
+    }
+
+    #[inline]
+    fn increment_nonzero(&self) -> bool {
+        let nonzero = self.0.get() != 0;
+        if nonzero {
+            self.increment();
+        }
+        nonzero
+    }
+
+    #[inline]
+    fn decrement(&self) -> bool {
+        let count = self.0.get().wrapping_sub(1);
+        self.0.set(count);
+        count == 0
+    }
+
+    #[inline]
+    fn release_unique(&self) -> bool {
+        let unique = self.0.get() == 1;
+        if unique {
+            self.0.set(0);
+        }
+        unique
+    }
+
+    #[inline]
+    fn lock_unique(&self) -> bool {
+        // Nothing can increment the count while it's checked, so it doesn't have to be locked
+        self.0.get() == 1
+    }
+
+    #[inline]
+    fn unlock(&self) {}
+
+    #[inline]
+    fn increment_unlocked(&self) {
+        self.increment();
+    }
+}
+
+/// An atomic counter that saturates instead of aborting, the value becomes immortal once it does.
+///
+/// This is useful for values that are shared so often that they should just live forever, at the
+/// cost of a compare-exchange loop for every change of the count.
+#[derive(Debug)]
+pub struct SaturatingCounter(AtomicUsize);
+
+impl SaturatingCounter {
+    /// The count of an immortal value, which never changes again.
+    const IMMORTAL: usize = usize::MAX >> 1;
+
+    /// The count while it's locked by `lock_unique`.
+    const LOCKED: usize = usize::MAX;
+
+    /// Changes the count unless it's immortal, returns the previous count.
+    fn update(&self, ordering: Ordering, change: fn(usize) -> Option<usize>) -> Option<usize> {
+        self.0
+            .fetch_update(ordering, Ordering::Relaxed, |count| {
+                if count >= Self::IMMORTAL {
+                    None
+                } else {
+                    change(count)
+                }
+            })
+            .ok()
+    }
+}
+
+/// Safety: Every method is atomic, and an immortal count never changes again.
+unsafe impl Counter for SaturatingCounter {
+    const NAME: &'static str = "CountedPointer";
+    const WEAK_NAME: &'static str = "CountedWeakPointer";
+
+    #[inline]
+    fn one() -> Self {
+        Self(AtomicUsize::new(1))
+    }
+
+    #[inline]
+    fn zero() -> Self {
+        Self(AtomicUsize::new(0))
+    }
+
+    #[inline]
+    fn initialize(&self) {
+        self.0.store(1, Ordering::Release);
+    }
+
+    #[inline]
+    fn count(&self) -> usize {
+        match self.0.load(Ordering::Acquire) {
+            // The count is only locked while it's 1
+            Self::LOCKED => 1,
+            count => count,
+        }
+    }
+
+    #[inline]
+    fn increment(&self) {
+        // The count can't be locked, as a reference to the allocation exists
+        self.update(Ordering::Relaxed, |count| Some(count.wrapping_add(1)));
+    }
+
+    #[inline]
+    fn increment_nonzero(&self) -> bool {
+        // An immortal value is never dropped, so it can always be upgraded
+        self.update(Ordering::Acquire, |count| {
+            (count != 0).then(|| count.wrapping_add(1))
+        })
+        .is_some()
+            || self.0.load(Ordering::Acquire) >= Self::IMMORTAL
+    }
+
+    #[inline]
+    fn decrement(&self) -> bool {
+        // Release makes sure every use of the value happens before it is dropped
+        if self.update(Ordering::Release, |count| Some(count.wrapping_sub(1))) == Some(1) {
+            // Synchronize with the decrements of other references
+            atomic::fence(Ordering::Acquire);
+            true
+        } else {
+            false
+        }
+    }
+
+    #[inline]
+    fn release_unique(&self) -> bool {
+        self.0
+            .compare_exchange(1, 0, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    #[inline]
+    fn lock_unique(&self) -> bool {
+        self.0
+            .compare_exchange(1, Self::LOCKED, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    #[inline]
+    fn unlock(&self) {
+        self.0.store(1, Ordering::Release);
+    }
+
+    #[inline]
+    fn increment_unlocked(&self) {
+        // A locked count is treated like an immortal one by update, so wait until it's unlocked
+        // Acquire synchronizes with the Release store unlocking the count
+        while self
+            .update(Ordering::Acquire, |count| Some(count.wrapping_add(1)))
+            .is_none()
+            && self.0.load(Ordering::Relaxed) == Self::LOCKED
+        {
+            hint::spin_loop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::AtomicUsize;
+
+    use super::{Counter as _, SaturatingCounter};
+
+    #[test]
+    fn saturation() {
+        // Create a count that is about to saturate
+        let counter = SaturatingCounter(AtomicUsize::new(SaturatingCounter::IMMORTAL - 1));
+
+        // Incrementing it makes it immortal
+        counter.increment();
+        assert_eq!(counter.count(), SaturatingCounter::IMMORTAL);
+
+        // An immortal count never changes again, so it never reaches 0
+        counter.increment();
+        assert!(!counter.decrement());
+        assert!(counter.increment_nonzero());
+        assert_eq!(counter.count(), SaturatingCounter::IMMORTAL);
+    }
+
+    #[test]
+    fn locking() {
+        // A count of 1 can be locked, and is still counted as 1
+        let counter = SaturatingCounter(AtomicUsize::new(1));
+        assert!(counter.lock_unique());
+        assert_eq!(counter.count(), 1);
+
+        // A locked count can't be locked or released again
+        assert!(!counter.lock_unique());
+        assert!(!counter.release_unique());
+
+        // After unlocking, the count can be incremented again
+        counter.unlock();
+        counter.increment_unlocked();
+        assert_eq!(counter.count(), 2);
+        assert!(!counter.lock_unique());
+    }
+}
@@ -0,0 +1,56 @@
+use core::error::Error;
+
+use crate::{allocator::Allocator, unique_pointer::UniquePointer};
+
+/// Only implemented for sized errors like for `Box`, so a `UniquePointer<dyn Error>` isn't an
+/// error itself, which would conflict with converting every error to it.
+impl<T: Error, A: Allocator> Error for UniquePointer<T, A> {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        T::source(self)
+    }
+}
+
+impl<'error, E: Error + 'error> From<E> for UniquePointer<dyn Error + 'error> {
+    #[inline]
+    fn from(error: E) -> Self {
+        unsize!(UniquePointer::new(error))
+    }
+}
+
+impl<'error, E: Error + Send + Sync + 'error> From<E>
+    for UniquePointer<dyn Error + Send + Sync + 'error>
+{
+    #[inline]
+    fn from(error: E) -> Self {
+        unsize!(UniquePointer::new(error))
+    }
+}
+
+/// Implements downcasting for a `UniquePointer` to a `dyn Error` with the given auto traits.
+macro_rules! downcast {
+    ($($auto_trait:ident),*) => {
+        impl<A: Allocator> UniquePointer<dyn Error $(+ $auto_trait)*, A> {
+            /// Converts the pointer to a pointer to the concrete error type, without moving the
+            /// error.
+            ///
+            /// # Errors
+            /// Returns the pointer if the error has a different type.
+            #[inline]
+            pub fn downcast<E: Error + 'static>(self) -> Result<UniquePointer<E, A>, Self> {
+                if (*self).is::<E>() {
+                    let (pointer, allocator) = self.into_parts();
+
+                    // Safety: The error has the type E, and stays in the same allocation
+                    Ok(unsafe { UniquePointer::from_parts(pointer.cast(), allocator) })
+                } else {
+                    Err(self)
+                }
+            }
+        }
+    };
+}
+
+downcast!();
+downcast!(Send);
+downcast!(Send, Sync);
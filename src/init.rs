@@ -0,0 +1,89 @@
+use core::{
+    marker::PhantomData,
+    mem::{ManuallyDrop, MaybeUninit},
+    ops::Deref,
+    ptr,
+};
+
+/// Makes a lifetime invariant, so a `Slot` can only be turned into the `Initialized` of that slot.
+type Brand<'slot> = PhantomData<fn(&'slot ()) -> &'slot ()>;
+
+/// Uninitialized memory for a value, inside the allocation of a new pointer.
+///
+/// The memory never moves, so its address can be stored in the value while initializing it.
+///
+/// ```
+/// use core::{marker::PhantomPinned, ptr};
+/// use smart_pointers::UniquePointer;
+///
+/// struct SelfReferential {
+///     value: u32,
+///     this: *const SelfReferential,
+///     _pinned: PhantomPinned,
+/// }
+///
+/// let pointer = UniquePointer::<SelfReferential>::pin_with(|mut slot| {
+///     let this = slot.as_mut_ptr().cast_const();
+///     slot.write(SelfReferential { value: 5, this, _pinned: PhantomPinned })
+/// });
+/// assert_eq!(pointer.value, 5);
+/// assert!(ptr::eq(pointer.this, &*pointer));
+/// ```
+pub struct Slot<'slot, T>(&'slot mut MaybeUninit<T>, Brand<'slot>);
+
+impl<'slot, T> Slot<'slot, T> {
+    pub(crate) const fn new(slot: &'slot mut MaybeUninit<T>) -> Self {
+        Self(slot, PhantomData)
+    }
+
+    /// Returns a pointer to the memory, which stays valid after the initialization.
+    #[inline]
+    pub const fn as_mut_ptr(&mut self) -> *mut T {
+        self.0.as_mut_ptr()
+    }
+
+    /// Initializes the memory with the value.
+    #[inline]
+    pub fn write(self, value: T) -> Initialized<'slot, T> {
+        Initialized(self.0.write(value), PhantomData)
+    }
+
+    /// Marks the memory as initialized, after it has been written through `as_mut_ptr`.
+    ///
+    /// # Safety
+    /// The memory has to contain a valid value.
+    #[inline]
+    pub const unsafe fn assume_init(self) -> Initialized<'slot, T> {
+        // Safety: The caller guarantees the value is initialized
+        Initialized(unsafe { self.0.assume_init_mut() }, PhantomData)
+    }
+}
+
+/// Proof that a `Slot` has been initialized, the value is dropped if it isn't returned.
+pub struct Initialized<'slot, T>(&'slot mut T, Brand<'slot>);
+
+impl<T> Initialized<'_, T> {
+    /// Hands the value over to the pointer that owns the memory.
+    pub(crate) const fn finish(self) {
+        // The value now belongs to the pointer, so it shouldn't be dropped
+        let _value = ManuallyDrop::new(self);
+    }
+}
+
+impl<T> Deref for Initialized<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.0
+    }
+}
+
+impl<T> Drop for Initialized<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        // The initialization was abandoned, so the value is dropped before the memory is freed
+        // Safety: The value is initialized and never used again
+        unsafe { ptr::drop_in_place(self.0) }
+    }
+}
@@ -16,10 +16,66 @@
     clippy::use_debug,
     clippy::redundant_clone,
     clippy::min_ident_chars,
-    clippy::must_use_candidate
+    clippy::must_use_candidate,
+    clippy::arbitrary_source_item_ordering,
+    reason = "These lints conflict with the style of this crate"
 )]
+/// Converts a `UniquePointer` or `SharedPointer` to a pointer to an unsized type, like a slice or
+/// trait object.
+///
+/// The conversion only compiles if the pointed to type can be unsized to the target type.
+///
+/// ```
+/// use core::fmt::Debug;
+/// use smart_pointers::{unsize, SharedPointer, UniquePointer};
+///
+/// let pointer: UniquePointer<dyn Debug> = unsize!(UniquePointer::new(5));
+/// assert_eq!(format!("{pointer:?}"), "UniquePointer(5)");
+///
+/// let pointer: SharedPointer<dyn Debug> = unsize!(SharedPointer::new(5));
+/// assert_eq!(format!("{pointer:?}"), "SharedPointer(5)");
+/// ```
+#[macro_export]
+macro_rules! unsize {
+    ($pointer:expr) => {{
+        // Evaluate the expression outside of the unsafe blocks
+        let pointer = $pointer;
+
+        // Safety: The raw pointer is only passed to the rebuilder it was returned with. A type that
+        // only pretends to be a pointer can't return a rebuilder, as it can only be taken from a
+        // real pointer by this unsafe function.
+        let (raw, rebuilder) = unsafe { pointer.__into_unsize_parts() };
+
+        // Safety: The pointer is only coerced by passing it, which is limited to unsizing coercions
+        unsafe { rebuilder.__rebuild(raw) }
+    }};
+}
+
+mod allocator;
+mod atomic_shared_pointer;
+mod atomic_unique_pointer;
+#[cfg(doctest)]
+mod auto_traits;
+mod counter;
+#[cfg(feature = "std")]
+mod error;
+mod init;
+mod mapped_pointer;
 mod shared_pointer;
 mod unique_pointer;
+mod unique_shared_pointer;
 
-pub use shared_pointer::SharedPointer;
+pub use allocator::{AllocError, Allocator, Global};
+pub use atomic_shared_pointer::{AtomicOptionSharedPointer, AtomicSharedPointer};
+pub use atomic_unique_pointer::AtomicUniquePointer;
+pub use counter::{AtomicCounter, AtomicCounter32, Counter, LocalCounter, SaturatingCounter};
+pub use init::{Initialized, Slot};
+pub use mapped_pointer::MappedPointer;
+pub use shared_pointer::{
+    CountedPointer, CountedWeakPointer, LocalSharedPointer, LocalWeakPointer, SharedPointer,
+    WeakPointer,
+};
 pub use unique_pointer::UniquePointer;
+pub use unique_shared_pointer::{
+    UniqueCountedPointer, UniqueLocalSharedPointer, UniqueSharedPointer,
+};
@@ -1,10 +1,6 @@
-use std::{
-    env::args,
-    sync::{Arc, Mutex},
-    time::Instant,
-};
+use std::{env::args, rc::Rc, sync::Arc, time::Instant};
 
-use smart_pointers::{SharedPointer, UniquePointer};
+use smart_pointers::{LocalSharedPointer, SharedPointer, UniquePointer};
 
 fn unique_pointer_test(print: bool) {
     let pointer = UniquePointer::new(1);
@@ -24,8 +20,21 @@ fn shared_pointer_test(print: bool) {
     let pointer2 = SharedPointer::new(2);
     let pointer3 = pointer.clone();
 
-    let pointer4 = SharedPointer::new(Mutex::new(Vec::with_capacity(10)));
-    pointer4.lock().unwrap().push(1);
+    let mut pointer4 = SharedPointer::new(Vec::with_capacity(10));
+    SharedPointer::make_mut(&mut pointer4).push(1);
+
+    if print {
+        println!("{}", *pointer2 + *pointer3);
+    }
+}
+
+fn local_shared_pointer_test(print: bool) {
+    let pointer = LocalSharedPointer::new(1);
+    let pointer2 = LocalSharedPointer::new(2);
+    let pointer3 = pointer.clone();
+
+    let mut pointer4 = LocalSharedPointer::new(Vec::with_capacity(10));
+    LocalSharedPointer::make_mut(&mut pointer4).push(1);
 
     if print {
         println!("{}", *pointer2 + *pointer3);
@@ -50,8 +59,21 @@ fn arc_test(print: bool) {
     let pointer2 = Arc::new(2);
     let pointer3 = pointer.clone();
 
-    let pointer4 = Arc::new(Mutex::new(Vec::<i32>::with_capacity(10)));
-    pointer4.lock().unwrap().push(1);
+    let mut pointer4 = Arc::new(Vec::<i32>::with_capacity(10));
+    Arc::make_mut(&mut pointer4).push(1);
+
+    if print {
+        println!("{}", *pointer2 + *pointer3);
+    }
+}
+
+fn rc_test(print: bool) {
+    let pointer = Rc::new(1);
+    let pointer2 = Rc::new(2);
+    let pointer3 = pointer.clone();
+
+    let mut pointer4 = Rc::new(Vec::<i32>::with_capacity(10));
+    Rc::make_mut(&mut pointer4).push(1);
 
     if print {
         println!("{}", *pointer2 + *pointer3);
@@ -69,6 +91,9 @@ fn memory_leak_test() {
     print_test_mark("Start Arc test");
     shared_pointer_test(true);
     print_test_mark("End Arc test");
+    print_test_mark("Start Rc test");
+    local_shared_pointer_test(true);
+    print_test_mark("End Rc test");
 }
 
 fn test_performance(function: impl Fn(bool), name: &str) {
@@ -86,6 +111,8 @@ fn performance_test() {
     test_performance(shared_pointer_test, "Shared pointer");
     test_performance(box_test, "Box");
     test_performance(arc_test, "Arc");
+    test_performance(local_shared_pointer_test, "Local shared pointer");
+    test_performance(rc_test, "Rc");
 }
 
 fn main() {
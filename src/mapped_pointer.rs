@@ -0,0 +1,122 @@
+use core::{fmt, ops::Deref, ptr};
+
+use crate::{
+    allocator::{Allocator, Global},
+    counter::{AtomicCounter, Counter},
+    shared_pointer::CountedPointer,
+};
+
+/// A pointer to a part of the value of a `SharedPointer`, like a field, which keeps the whole
+/// value alive.
+///
+/// It shares the strong reference count of the `SharedPointer` it was mapped from, so cloning and
+/// dropping it counts like cloning and dropping that `SharedPointer`.
+///
+/// `MappedPointer::owner` is an associated function, so it doesn't hide methods of the value.
+pub struct MappedPointer<T: ?Sized, U: ?Sized, A: Allocator = Global, C: Counter = AtomicCounter>(
+    CountedPointer<T, A, C>,
+    ptr::NonNull<U>,
+);
+
+/// Safety: The projected value is only accessed through shared references, like the whole value.
+unsafe impl<T: ?Sized, U: ?Sized + Sync, A: Allocator, C: Counter> Send
+    for MappedPointer<T, U, A, C>
+where
+    CountedPointer<T, A, C>: Send,
+{
+}
+
+/// Safety: The projected value is only accessed through shared references, like the whole value.
+unsafe impl<T: ?Sized, U: ?Sized + Sync, A: Allocator, C: Counter> Sync
+    for MappedPointer<T, U, A, C>
+where
+    CountedPointer<T, A, C>: Sync,
+{
+}
+
+impl<T: ?Sized, U: ?Sized, A: Allocator, C: Counter> MappedPointer<T, U, A, C> {
+    /// Creates a `MappedPointer` from the owner and a pointer to a part of its value.
+    ///
+    /// # Safety
+    /// The projected value has to stay alive as long as the value of the owner.
+    pub(crate) const unsafe fn from_parts(
+        owner: CountedPointer<T, A, C>,
+        projected: ptr::NonNull<U>,
+    ) -> Self {
+        Self(owner, projected)
+    }
+
+    /// Returns the number of `SharedPointer`s to the whole value, including mapped ones.
+    #[inline]
+    pub fn reference_count(&self) -> usize {
+        self.0.reference_count()
+    }
+
+    /// Returns the `SharedPointer` to the whole value.
+    #[inline]
+    pub const fn owner(pointer: &Self) -> &CountedPointer<T, A, C> {
+        &pointer.0
+    }
+
+    /// Converts the pointer back to a `SharedPointer` to the whole value, keeping its strong
+    /// reference.
+    #[inline]
+    pub fn into_owner(pointer: Self) -> CountedPointer<T, A, C> {
+        pointer.0
+    }
+
+    /// Returns a pointer to the projected value, which stays valid as long as a `SharedPointer` to
+    /// the whole value exists.
+    #[inline]
+    pub const fn as_ptr(pointer: &Self) -> *const U {
+        pointer.1.as_ptr()
+    }
+
+    /// Creates a pointer to a part of the projected value, which still keeps the whole value
+    /// alive.
+    #[inline]
+    pub fn map<V: ?Sized, F>(pointer: Self, project: F) -> MappedPointer<T, V, A, C>
+    where
+        F: FnOnce(&U) -> &V,
+    {
+        // The new projection borrows from the old one, so it's alive as long as the whole value
+        let projected = ptr::NonNull::from(project(&pointer));
+        // Safety: The new projection borrows from the value kept alive by the owner
+        unsafe { MappedPointer::from_parts(pointer.0, projected) }
+    }
+}
+
+impl<T: ?Sized, U: ?Sized, A: Allocator + Clone, C: Counter> Clone for MappedPointer<T, U, A, C> {
+    #[inline]
+    fn clone(&self) -> Self {
+        // Cloning the owner increments the shared strong reference count
+        Self(self.0.clone(), self.1)
+    }
+}
+
+impl<T: ?Sized, U: ?Sized, A: Allocator, C: Counter> AsRef<U> for MappedPointer<T, U, A, C> {
+    #[inline]
+    fn as_ref(&self) -> &U {
+        // Safety: The projected value is part of the value kept alive by the owner
+        unsafe { self.1.as_ref() }
+    }
+}
+
+impl<T: ?Sized, U: ?Sized, A: Allocator, C: Counter> Deref for MappedPointer<T, U, A, C> {
+    type Target = U;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_ref()
+    }
+}
+
+impl<T: ?Sized, U: ?Sized + fmt::Debug, A: Allocator, C: Counter> fmt::Debug
+    for MappedPointer<T, U, A, C>
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Only the projected value is shown, like the value of a SharedPointer
+        f.write_fmt(format_args!("MappedPointer({:?})", self.as_ref()))
+    }
+}
@@ -1,89 +1,846 @@
 use core::{
     alloc::Layout,
+    any::Any,
+    borrow::Borrow,
+    cmp::Ordering,
+    convert::Infallible,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    mem::{ManuallyDrop, MaybeUninit},
     ops::Deref,
+    panic::{RefUnwindSafe, UnwindSafe},
+    pin::Pin,
     ptr,
-    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+use crate::{
+    allocator::{self, AllocError, Allocator, Global},
+    counter::{AtomicCounter, Counter, LocalCounter},
+    init::{Initialized, Slot},
+    mapped_pointer::MappedPointer,
+    unique_pointer::UniquePointer,
+    unique_shared_pointer::UniqueCountedPointer,
 };
 
 extern crate alloc;
 
+/// The allocation shared by `SharedPointer`s and `WeakPointer`s.
+///
+/// The counters are stored before the value, so the value can be unsized.
 #[derive(Debug)]
-struct ReferenceCounter<T>(T, AtomicUsize);
+#[repr(C)]
+pub struct ReferenceCounter<T: ?Sized, C> {
+    /// Number of `SharedPointer`s to the value.
+    strong: C,
+
+    /// Number of `WeakPointer`s to the allocation, plus one for all `SharedPointer`s together.
+    weak: C,
 
-pub struct SharedPointer<T>(ptr::NonNull<ReferenceCounter<T>>);
+    value: T,
+}
+
+/// Returns the offset of the value in a `ReferenceCounter`, for a value with the given alignment.
+const fn value_offset<C>(align: usize) -> usize {
+    // The value is stored after the counters, padded to its alignment
+    size_of::<ReferenceCounter<(), C>>().next_multiple_of(align)
+}
+
+/// A pointer to a value with reference counting, the value is dropped with the last strong
+/// pointer to it.
+///
+/// How the references are counted is chosen by the `Counter`, usually through one of the aliases
+/// `SharedPointer` or `LocalSharedPointer`.
+///
+/// The `PhantomData` tells the drop checker that the pointer may drop a `T`.
+///
+/// The memory is allocated by `A`, which is stored in every pointer to free it again.
+///
+/// Functions like `CountedPointer::get_mut` are associated functions instead of methods, so they
+/// don't hide methods of the value.
+///
+/// Pinning requires a `'static` allocator, so the memory can't be reused without dropping the
+/// value.
+pub struct CountedPointer<T: ?Sized, A: Allocator = Global, C: Counter = AtomicCounter>(
+    ptr::NonNull<ReferenceCounter<T, C>>,
+    PhantomData<ReferenceCounter<T, C>>,
+    A,
+);
+
+/// A pointer to a value that can be shared between threads, with atomic reference counting.
+pub type SharedPointer<T, A = Global> = CountedPointer<T, A, AtomicCounter>;
+
+/// A pointer to a value that stays on one thread, with cheaper reference counting.
+pub type LocalSharedPointer<T, A = Global> = CountedPointer<T, A, LocalCounter>;
 
 /// Safety:
-/// Counter is atomic and mutable access to the value is impossible without interior mutability.
-unsafe impl<T: Send + Sync> Send for SharedPointer<T> {}
+/// Counter is thread-safe and mutable access to the value is impossible without interior
+/// mutability.
+unsafe impl<T: ?Sized + Send + Sync, A: Allocator + Send, C: Counter + Sync> Send
+    for CountedPointer<T, A, C>
+{
+}
 
 /// Safety:
-/// Counter is atomic and mutable access to the value is impossible without interior mutability.
-unsafe impl<T: Send + Sync> Sync for SharedPointer<T> {}
+/// Counter is thread-safe and mutable access to the value is impossible without interior
+/// mutability.
+unsafe impl<T: ?Sized + Send + Sync, A: Allocator + Sync, C: Counter + Sync> Sync
+    for CountedPointer<T, A, C>
+{
+}
 
-impl<T> SharedPointer<T> {
-    fn allocate_memory() -> ptr::NonNull<ReferenceCounter<T>> {
-        // Allocate memory
-        // Safety: allocation will be checked for NULL before returning it.
-        let pointer = unsafe { alloc::alloc::alloc(Layout::new::<ReferenceCounter<T>>()) };
+/// Moving the pointer doesn't move the value, so it doesn't matter whether `T` is `Unpin`.
+impl<T: ?Sized, A: Allocator, C: Counter> Unpin for CountedPointer<T, A, C> {}
+
+/// The value can't be mutated without interior mutability, so only shared access has to be safe.
+impl<T: ?Sized + RefUnwindSafe, A: Allocator + UnwindSafe, C: Counter> UnwindSafe
+    for CountedPointer<T, A, C>
+{
+}
+
+/// Rebuilds a `CountedPointer` from a coerced pointer, used by the `unsize` macro.
+#[doc(hidden)]
+pub struct SharedRebuilder<A, C>(A, PhantomData<C>);
+
+impl<A: Allocator, C: Counter> SharedRebuilder<A, C> {
+    /// # Safety
+    /// The pointer has to be taken from a `CountedPointer` by `__into_unsize_parts`, together with
+    /// this rebuilder, only coercions are allowed before passing it.
+    #[inline]
+    pub unsafe fn __rebuild<T: ?Sized>(
+        self,
+        pointer: *mut ReferenceCounter<T, C>,
+    ) -> CountedPointer<T, A, C> {
+        // Safety: The pointer was taken from a CountedPointer, so it can't be NULL
+        let non_null = unsafe { ptr::NonNull::new_unchecked(pointer) };
+
+        // Safety: A coercion keeps the pointer pointing to the same reference counter
+        unsafe { CountedPointer::from_parts(non_null, self.0) }
+    }
+}
+
+impl<T: ?Sized, A: Allocator, C: Counter> CountedPointer<T, A, C> {
+    #[inline]
+    pub fn reference_count(&self) -> usize {
+        self.inner().strong.count()
+    }
+
+    /// Returns the number of `WeakPointer`s to the value.
+    #[inline]
+    pub fn weak_count(&self) -> usize {
+        // Subtract the weak reference shared by all `SharedPointer`s
+        self.inner().weak.count().saturating_sub(1)
+    }
+
+    /// Returns the allocator the value is stored in.
+    #[inline]
+    pub const fn allocator(&self) -> &A {
+        &self.2
+    }
+
+    /// Splits the `SharedPointer` into a pointer that can be coerced, used by the `unsize` macro.
+    ///
+    /// # Safety
+    /// The pointer may only be passed to `__rebuild` of the returned rebuilder, after an unsizing
+    /// coercion, otherwise the reference is leaked.
+    #[doc(hidden)]
+    #[inline]
+    pub unsafe fn __into_unsize_parts(
+        self,
+    ) -> (*mut ReferenceCounter<T, C>, SharedRebuilder<A, C>) {
+        let (pointer, allocator) = self.into_parts();
+        (pointer.as_ptr(), SharedRebuilder(allocator, PhantomData))
+    }
+
+    /// Splits the `SharedPointer` into the pointer and the allocator, without changing the count.
+    fn into_parts(self) -> (ptr::NonNull<ReferenceCounter<T, C>>, A) {
+        // The reference is now owned by the returned pointer, so the count shouldn't change
+        let pointer = ManuallyDrop::new(self);
+
+        // Safety: The allocator is moved out of a pointer that will never be used again
+        let allocator = unsafe { ptr::read(&raw const pointer.2) };
+        (pointer.0, allocator)
+    }
+
+    /// Creates a `SharedPointer` from a pointer owning a strong reference and its allocator.
+    ///
+    /// # Safety
+    /// The pointer has to point to a reference counter with an initialized value, allocated by the
+    /// allocator, and the strong reference it owns is taken over.
+    const unsafe fn from_parts(
+        pointer: ptr::NonNull<ReferenceCounter<T, C>>,
+        allocator: A,
+    ) -> Self {
+        Self(pointer, PhantomData, allocator)
+    }
+
+    const fn inner(&self) -> &ReferenceCounter<T, C> {
+        // Safety: Pointer can't be null
+        unsafe { self.0.as_ref() }
+    }
+
+    /// Checks whether this is the only pointer to the value, including `WeakPointer`s.
+    fn is_unique(&self) -> bool {
+        // Lock the weak count, so no WeakPointer can be created while the strong count is checked
+        // Acquire synchronizes with the Release decrement of dropped WeakPointers
+        if self.inner().weak.lock_unique() {
+            // Synchronizes with the decrements of dropped SharedPointers
+            let unique = self.inner().strong.count() == 1;
+
+            // The check happens before new WeakPointers are created
+            self.inner().weak.unlock();
+            unique
+        } else {
+            false
+        }
+    }
+
+    /// Returns a mutable reference to the value, if no other `SharedPointer` or `WeakPointer` to it
+    /// exists.
+    #[inline]
+    pub fn get_mut(pointer: &mut Self) -> Option<&mut T> {
+        // Safety: No other pointer can access the value, and the reference borrows this pointer
+        pointer
+            .is_unique()
+            .then(|| unsafe { Self::get_mut_unchecked(pointer) })
+    }
+
+    /// Returns a mutable reference to the value, without checking whether other pointers exist.
+    ///
+    /// # Safety
+    /// No other `SharedPointer` or `WeakPointer` may access the value while the reference is alive.
+    pub(crate) const unsafe fn get_mut_unchecked(pointer: &mut Self) -> &mut T {
+        // Safety: The caller guarantees that the value isn't accessed through other pointers
+        unsafe { &mut (*pointer.0.as_ptr()).value }
+    }
+
+    /// Converts the pointer to a `UniqueSharedPointer`, if no other `SharedPointer` or
+    /// `WeakPointer` to the value exists.
+    ///
+    /// # Errors
+    /// Returns the pointer if other `SharedPointer`s or `WeakPointer`s to the value exist.
+    #[inline]
+    pub fn try_into_unique(pointer: Self) -> Result<UniqueCountedPointer<T, A, C>, Self> {
+        if pointer.is_unique() {
+            // Safety: No other pointer exists, and none can be created without this one
+            Ok(unsafe { UniqueCountedPointer::from_shared(pointer) })
+        } else {
+            Err(pointer)
+        }
+    }
+
+    /// Returns a pointer to the value, which stays valid as long as a `SharedPointer` to it exists.
+    #[inline]
+    pub const fn as_ptr(pointer: &Self) -> *const T {
+        // Safety: The reference counter is allocated, the value isn't accessed
+        unsafe { &raw const (*pointer.0.as_ptr()).value }
+    }
+
+    /// Checks whether both pointers point to the same value, like `ptr::addr_eq`.
+    #[inline]
+    pub fn ptr_eq(pointer: &Self, other: &Self) -> bool {
+        ptr::addr_eq(pointer.0.as_ptr(), other.0.as_ptr())
+    }
+
+    /// Converts the `SharedPointer` to a pointer to the value and its allocator, the strong
+    /// reference is kept until the pointer is passed to `from_raw_in`.
+    #[inline]
+    pub fn into_raw_with_allocator(pointer: Self) -> (*const T, A) {
+        let raw = Self::as_ptr(&pointer);
+        (raw, pointer.into_parts().1)
+    }
+
+    /// Converts a pointer returned by `into_raw_with_allocator` back to a `SharedPointer`.
+    ///
+    /// # Safety
+    /// The pointer has to be returned by `into_raw` or `into_raw_with_allocator` of a
+    /// `CountedPointer<T, A, C>`, and the allocator has to be the one returned with it, or a clone
+    /// of it. The strong reference kept by the pointer is taken over, so every pointer returned by
+    /// `into_raw_with_allocator` may only be converted back once.
+    #[inline]
+    pub const unsafe fn from_raw_in(pointer: *const T, allocator: A) -> Self {
+        // Safety: The caller guarantees that the pointer points to a value in a reference counter
+        unsafe { Self::from_parts(Self::counter_from_raw(pointer), allocator) }
+    }
+
+    /// Calculates the pointer to the reference counter, from a pointer to the value stored in it.
+    ///
+    /// # Safety
+    /// The pointer has to point to the value of a live reference counter.
+    #[expect(
+        clippy::as_conversions,
+        reason = "Casting is the only way to keep the metadata of a pointer to an unsized value"
+    )]
+    const unsafe fn counter_from_raw(pointer: *const T) -> ptr::NonNull<ReferenceCounter<T, C>> {
+        // The value is still alive, so its alignment can be read from it
+        // Safety: The caller guarantees that the value is alive
+        let offset = value_offset::<C>(align_of_val(unsafe { &*pointer }));
+
+        // Safety: The counters are stored in the same allocation, in front of the value
+        let counter = unsafe { pointer.byte_sub(offset) } as *mut ReferenceCounter<T, C>;
+
+        // Safety: The counter is at the start of an allocation, so it can't be NULL
+        unsafe { ptr::NonNull::new_unchecked(counter) }
+    }
+
+    /// Leaks a strong reference to the value, so it's never dropped.
+    #[inline]
+    pub fn leak<'value>(pointer: Self) -> &'value T
+    where
+        A: 'value,
+    {
+        // The allocator is leaked as well, so it can't be dropped while the memory is in use
+        let leaked = ManuallyDrop::new(pointer);
+
+        // Safety: The strong reference keeps the value alive forever
+        unsafe { &(*leaked.0.as_ptr()).value }
+    }
+
+    /// Creates a pointer to a part of the value, like a field, which keeps the whole value alive
+    /// and shares its reference count.
+    #[inline]
+    pub fn map<U: ?Sized, F>(pointer: Self, project: F) -> MappedPointer<T, U, A, C>
+    where
+        F: FnOnce(&T) -> &U,
+    {
+        // The reference can only borrow from the value, or from something that lives forever
+        let projected = ptr::NonNull::from(project(&pointer));
+
+        // Safety: The value stays alive as long as the strong reference moved into the owner
+        unsafe { MappedPointer::from_parts(pointer, projected) }
+    }
+}
+
+impl<T: ?Sized, C: Counter> CountedPointer<T, Global, C> {
+    /// Converts the `SharedPointer` to a pointer to the value, the strong reference is kept until
+    /// the pointer is passed to `from_raw` or `decrement_strong_count`.
+    #[inline]
+    pub fn into_raw(pointer: Self) -> *const T {
+        Self::into_raw_with_allocator(pointer).0
+    }
+
+    /// Converts a pointer returned by `into_raw` back to a `SharedPointer`.
+    ///
+    /// # Safety
+    /// The pointer has to be returned by `into_raw` of a `SharedPointer<T>`. The strong reference
+    /// kept by the pointer is taken over, so every pointer returned by `into_raw` may only be
+    /// converted back once.
+    #[inline]
+    pub const unsafe fn from_raw(pointer: *const T) -> Self {
+        // Safety: The caller guarantees that the pointer was returned by into_raw
+        unsafe { Self::from_raw_in(pointer, Global) }
+    }
+
+    /// Increments the strong reference count of the value, through a pointer returned by
+    /// `into_raw`.
+    ///
+    /// # Safety
+    /// The pointer has to be returned by `into_raw` of a `SharedPointer<T>`, and the strong
+    /// reference kept by it mustn't have been released yet.
+    #[inline]
+    pub unsafe fn increment_strong_count(pointer: *const T) {
+        // Safety: The caller guarantees that the value is alive
+        let counter = unsafe { Self::counter_from_raw(pointer) };
+
+        // Safety: The strong reference kept by the pointer keeps the counter alive
+        unsafe { counter.as_ref() }.strong.increment();
+    }
+
+    /// Decrements the strong reference count of the value, through a pointer returned by
+    /// `into_raw`, and drops the value if it was the last strong reference.
+    ///
+    /// # Safety
+    /// The pointer has to be returned by `into_raw` of a `SharedPointer<T>`, and the strong
+    /// reference kept by it is released, so it can't be used afterwards unless another strong
+    /// reference exists.
+    #[inline]
+    pub unsafe fn decrement_strong_count(pointer: *const T) {
+        // Safety: The caller guarantees that the pointer owns a strong reference
+        drop(unsafe { Self::from_raw(pointer) });
+    }
+}
+
+impl<T: ?Sized, A: Allocator + Clone, C: Counter> CountedPointer<T, A, C> {
+    /// Creates a `WeakPointer` to the value, which doesn't keep the value alive.
+    #[inline]
+    pub fn downgrade(&self) -> CountedWeakPointer<T, A, C> {
+        // Increment the weak reference count, after waiting for a uniqueness check to finish
+        self.inner().weak.increment_unlocked();
 
-        // Store the pointer in a non-null pointer and return it
-        ptr::NonNull::new(pointer.cast()).expect("No memory")
+        // Copy the pointer to a new WeakPointer and return it
+        CountedWeakPointer(self.0, self.2.clone())
     }
+}
 
+impl<T, C: Counter> CountedPointer<T, Global, C> {
     #[inline]
     pub fn new(value: T) -> Self {
+        Self::new_in(value, Global)
+    }
+
+    /// Stores the value in a new `SharedPointer`, without panicking if no memory could be
+    /// allocated.
+    ///
+    /// # Errors
+    /// Returns an `AllocError` if no memory could be allocated.
+    #[inline]
+    pub fn try_new(value: T) -> Result<Self, AllocError> {
+        Self::try_new_in(value, Global)
+    }
+
+    /// Stores the value in a new pinned `SharedPointer`, so it can never be moved again.
+    #[inline]
+    pub fn pin(value: T) -> Pin<Self> {
+        Self::pin_in(value, Global)
+    }
+
+    /// Initializes the value directly in the allocated memory, so it never has to be moved.
+    ///
+    /// The pointer isn't pinned, so a value that stores its own address should use `pin_with`.
+    #[inline]
+    pub fn new_with<F>(init: F) -> Self
+    where
+        F: for<'slot> FnOnce(Slot<'slot, T>) -> Initialized<'slot, T>,
+    {
+        Self::new_with_in(init, Global)
+    }
+
+    /// Initializes the value directly in the allocated memory, the memory is freed again if the
+    /// initialization fails.
+    ///
+    /// # Errors
+    /// Returns the error of the initialization.
+    #[inline]
+    pub fn try_new_with<E, F>(init: F) -> Result<Self, E>
+    where
+        F: for<'slot> FnOnce(Slot<'slot, T>) -> Result<Initialized<'slot, T>, E>,
+    {
+        Self::try_new_with_in(init, Global)
+    }
+
+    /// Initializes the value directly in the allocated memory and pins it, so a value that stores
+    /// its own address can never be moved.
+    #[inline]
+    pub fn pin_with<F>(init: F) -> Pin<Self>
+    where
+        F: for<'slot> FnOnce(Slot<'slot, T>) -> Initialized<'slot, T>,
+    {
+        Self::pin_with_in(init, Global)
+    }
+
+    /// Initializes the value directly in the allocated memory and pins it, the memory is freed
+    /// again if the initialization fails.
+    ///
+    /// # Errors
+    /// Returns the error of the initialization.
+    #[inline]
+    pub fn try_pin_with<E, F>(init: F) -> Result<Pin<Self>, E>
+    where
+        F: for<'slot> FnOnce(Slot<'slot, T>) -> Result<Initialized<'slot, T>, E>,
+    {
+        Self::try_pin_with_in(init, Global)
+    }
+
+    /// Creates a value that holds a `WeakPointer` to itself.
+    ///
+    /// The `WeakPointer` can't be upgraded until the closure has returned the value.
+    #[inline]
+    pub fn new_cyclic<F>(init: F) -> Self
+    where
+        F: FnOnce(&CountedWeakPointer<T, Global, C>) -> T,
+    {
+        Self::new_cyclic_in(init, Global)
+    }
+}
+
+impl<T, A: Allocator, C: Counter> CountedPointer<T, A, C> {
+    /// Stores the value in a new `SharedPointer`, using memory from the allocator.
+    #[inline]
+    pub fn new_in(value: T, allocator: A) -> Self {
+        Self::try_new_in(value, allocator).unwrap_or_else(|AllocError| {
+            alloc::alloc::handle_alloc_error(Layout::new::<ReferenceCounter<T, C>>())
+        })
+    }
+
+    /// Stores the value in a new `SharedPointer`, using memory from the allocator, without
+    /// panicking if no memory could be allocated.
+    ///
+    /// # Errors
+    /// Returns an `AllocError` if no memory could be allocated.
+    #[inline]
+    pub fn try_new_in(value: T, allocator: A) -> Result<Self, AllocError> {
         // Allocate memory
-        let pointer = Self::allocate_memory();
+        allocator::try_allocate(&allocator, Layout::new::<ReferenceCounter<T, C>>()).map(|memory| {
+            let pointer = memory.cast::<ReferenceCounter<T, C>>();
 
-        // Create a reference counter storing the value
-        let reference_counter = ReferenceCounter(value, AtomicUsize::new(1));
+            // Create a reference counter storing the value
+            let reference_counter = ReferenceCounter {
+                strong: C::one(),
+                weak: C::one(),
+                value,
+            };
 
-        // Store the reference counter at the address pointed to by the pointer
-        // Safety: Pointer has been checked for being NULL already
-        unsafe { pointer.as_ptr().write(reference_counter) }
+            // Store the reference counter at the address pointed to by the pointer
+            // Safety: Pointer has been checked for being NULL already
+            unsafe { pointer.as_ptr().write(reference_counter) }
 
-        // Store the pointer in a SharedPointer and return it
-        Self(pointer)
+            // Store the pointer in a SharedPointer and return it
+            Self(pointer, PhantomData, allocator)
+        })
     }
 
+    /// Stores the value in a new pinned `SharedPointer`, using memory from the allocator.
     #[inline]
-    pub fn reference_count(&self) -> usize {
-        self.inner().1.load(Ordering::Relaxed)
+    pub fn pin_in(value: T, allocator: A) -> Pin<Self>
+    where
+        A: 'static,
+    {
+        // Safety: The value is never moved by a SharedPointer, and no pointer to it exists yet that
+        // isn't pinned
+        unsafe { Pin::new_unchecked(Self::new_in(value, allocator)) }
     }
 
-    const fn inner(&self) -> &ReferenceCounter<T> {
-        // Safety: Pointer can't be null
-        unsafe { self.0.as_ref() }
+    /// Initializes the value directly in memory from the allocator, so it never has to be moved.
+    #[inline]
+    pub fn new_with_in<F>(init: F, allocator: A) -> Self
+    where
+        F: for<'slot> FnOnce(Slot<'slot, T>) -> Initialized<'slot, T>,
+    {
+        Self::try_new_with_in(|slot| Ok::<_, Infallible>(init(slot)), allocator)
+            .unwrap_or_else(|never| match never {})
+    }
+
+    /// Initializes the value directly in memory from the allocator, the memory is freed again if
+    /// the initialization fails.
+    ///
+    /// # Errors
+    /// Returns the error of the initialization.
+    #[inline]
+    pub fn try_new_with_in<E, F>(init: F, allocator: A) -> Result<Self, E>
+    where
+        F: for<'slot> FnOnce(Slot<'slot, T>) -> Result<Initialized<'slot, T>, E>,
+    {
+        // The uninitialized pointer frees the memory if the initialization fails or panics
+        let uninit = CountedPointer::<_, _, C>::new_in(MaybeUninit::<T>::uninit(), allocator);
+
+        // Safety: The pointer was just created, so nothing else can access the value
+        let slot = unsafe { &mut (*uninit.0.as_ptr()).value };
+        init(Slot::new(slot)).map(Initialized::finish).map(|()| {
+            let parts = uninit.into_parts();
+
+            // Safety: The Initialized proves that the value was initialized
+            unsafe { Self::from_parts(parts.0.cast(), parts.1) }
+        })
+    }
+
+    /// Initializes the value directly in memory from the allocator and pins it, so a value that
+    /// stores its own address can never be moved.
+    #[inline]
+    pub fn pin_with_in<F>(init: F, allocator: A) -> Pin<Self>
+    where
+        A: 'static,
+        F: for<'slot> FnOnce(Slot<'slot, T>) -> Initialized<'slot, T>,
+    {
+        Self::try_pin_with_in(|slot| Ok::<_, Infallible>(init(slot)), allocator)
+            .unwrap_or_else(|never| match never {})
     }
+
+    /// Initializes the value directly in memory from the allocator and pins it, the memory is freed
+    /// again if the initialization fails.
+    ///
+    /// # Errors
+    /// Returns the error of the initialization.
+    #[inline]
+    pub fn try_pin_with_in<E, F>(init: F, allocator: A) -> Result<Pin<Self>, E>
+    where
+        A: 'static,
+        F: for<'slot> FnOnce(Slot<'slot, T>) -> Result<Initialized<'slot, T>, E>,
+    {
+        Self::try_new_with_in(init, allocator).map(|pointer| {
+            // Safety: The value is never moved by a SharedPointer, and no pointer to it exists yet
+            // that isn't pinned
+            unsafe { Pin::new_unchecked(pointer) }
+        })
+    }
+
+    /// Creates a value that holds a `WeakPointer` to itself, using memory from the allocator.
+    ///
+    /// The `WeakPointer` can't be upgraded until the closure has returned the value, the memory is
+    /// freed again if the closure panics.
+    #[inline]
+    pub fn new_cyclic_in<F>(init: F, allocator: A) -> Self
+    where
+        F: FnOnce(&CountedWeakPointer<T, A, C>) -> T,
+    {
+        // Allocate memory
+        let layout = Layout::new::<ReferenceCounter<T, C>>();
+        let pointer = allocator::allocate(&allocator, layout).cast::<ReferenceCounter<T, C>>();
+
+        // Initialize the counters, without a strong reference as the value doesn't exist yet
+        // Safety: The allocation is large enough to store the counters
+        unsafe {
+            ptr::addr_of_mut!((*pointer.as_ptr()).strong).write(C::zero());
+            ptr::addr_of_mut!((*pointer.as_ptr()).weak).write(C::one());
+        }
+
+        // The WeakPointer owns the only weak reference, so it frees the memory if the closure
+        // panics
+        let weak = CountedWeakPointer(pointer, allocator);
+        let value = init(&weak);
+
+        // Store the value, and only then allow upgrading to it
+        // Safety: Nothing can access the value while the strong count is 0
+        unsafe { ptr::addr_of_mut!((*pointer.as_ptr()).value).write(value) }
+        weak.inner().strong.initialize();
+
+        // The weak reference becomes the one shared by all SharedPointers
+        let parts = weak.into_parts();
+
+        // Safety: The value is initialized, and the strong reference was created by initialize
+        unsafe { Self::from_parts(parts.0, parts.1) }
+    }
+
+    /// Moves the value out of the allocation, after the last strong reference was released.
+    ///
+    /// # Safety
+    /// The strong count has to be 0, and the strong reference of this pointer has been released.
+    unsafe fn take_value(self) -> T {
+        let (pointer, allocator) = self.into_parts();
+
+        // Safety: No SharedPointers are left, so the value can't be accessed anymore
+        let value = unsafe { ptr::read(&raw const (*pointer.as_ptr()).value) };
+
+        // Release the weak reference shared by all SharedPointers
+        drop(CountedWeakPointer(pointer, allocator));
+        value
+    }
+
+    /// Returns the value, if this is the only `SharedPointer` to it, or the pointer otherwise.
+    ///
+    /// # Errors
+    /// Returns the pointer if other `SharedPointer`s to the value exist.
+    #[inline]
+    pub fn try_unwrap(pointer: Self) -> Result<T, Self> {
+        // Release the strong reference, only if it's the last one
+        // Synchronizes with the decrements of dropped SharedPointers
+        if pointer.inner().strong.release_unique() {
+            // Safety: The last strong reference was released by this pointer
+            Ok(unsafe { pointer.take_value() })
+        } else {
+            Err(pointer)
+        }
+    }
+
+    /// Returns the value, if this is the last `SharedPointer` to it, or drops the pointer
+    /// otherwise.
+    ///
+    /// If every `SharedPointer` calls this, exactly one of them returns the value, even if they
+    /// are dropped concurrently.
+    #[inline]
+    pub fn into_inner(pointer: Self) -> Option<T> {
+        // Release the strong reference, only the last pointer continues
+        // Every use of the value by other pointers happens before the value is moved out
+        if pointer.inner().strong.decrement() {
+            // Safety: The last strong reference was released by this pointer
+            Some(unsafe { pointer.take_value() })
+        } else {
+            // The strong reference is already released, so only the allocator is dropped
+            let _allocator = pointer.into_parts().1;
+            None
+        }
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone, C: Counter> CountedPointer<T, A, C> {
+    /// Returns a mutable reference to the value, after cloning it into a new allocation if other
+    /// `SharedPointer`s or `WeakPointer`s to it exist.
+    #[inline]
+    pub fn make_mut(pointer: &mut Self) -> &mut T {
+        if !pointer.is_unique() {
+            // Clone the value, the other pointers keep the original value
+            *pointer = Self::new_in(T::clone(pointer), pointer.2.clone());
+        }
+
+        // Safety: No other pointer can access the value, and the reference borrows this pointer
+        unsafe { Self::get_mut_unchecked(pointer) }
+    }
+
+    /// Returns the value, if this is the only `SharedPointer` to it, or a clone of it otherwise.
+    #[inline]
+    pub fn unwrap_or_clone(pointer: Self) -> T {
+        Self::try_unwrap(pointer).unwrap_or_else(|shared| T::clone(&shared))
+    }
+}
+
+impl<T, C: Counter> CountedPointer<[T], Global, C> {
+    /// Allocates a reference counter for a slice, the elements still have to be initialized.
+    #[expect(
+        clippy::as_conversions,
+        reason = "Casting is the only way to add the length to a pointer to an unsized struct"
+    )]
+    fn allocate_slice(length: usize) -> ptr::NonNull<ReferenceCounter<[T], C>> {
+        // Calculate the layout of the counters followed by the elements
+        let layout = Layout::new::<ReferenceCounter<(), C>>()
+            .extend(Layout::array::<T>(length).expect("Slice too large"))
+            .expect("Slice too large")
+            .0
+            .pad_to_align();
+
+        // Allocate memory and add the length of the slice to the pointer
+        let elements = allocator::allocate(&Global, layout).cast::<T>();
+        let pointer = ptr::slice_from_raw_parts_mut(elements.as_ptr(), length)
+            as *mut ReferenceCounter<[T], C>;
+
+        // Initialize the counters
+        // Safety: The allocation is large enough to store the counters
+        unsafe {
+            ptr::addr_of_mut!((*pointer).strong).write(C::one());
+            ptr::addr_of_mut!((*pointer).weak).write(C::one());
+        }
+
+        // Safety: The pointer was checked for being NULL by allocate
+        unsafe { ptr::NonNull::new_unchecked(pointer) }
+    }
+}
+
+/// Implements downcasting for a `CountedPointer` to a `dyn Any` with the given auto traits.
+macro_rules! downcast {
+    ($($auto_trait:ident),*) => {
+        impl<A: Allocator, C: Counter> CountedPointer<dyn Any $(+ $auto_trait)*, A, C> {
+            /// Converts the pointer to a pointer to the concrete type, which keeps the allocation
+            /// and the reference count.
+            ///
+            /// # Errors
+            /// Returns the pointer if the value has a different type.
+            #[inline]
+            pub fn downcast<T>(self) -> Result<CountedPointer<T, A, C>, Self>
+            where
+                T: Any $(+ $auto_trait)*,
+            {
+                if (*self).is::<T>() {
+                    let (pointer, allocator) = self.into_parts();
+
+                    // Safety: The value has the type T, so the counter is a ReferenceCounter<T, C>
+                    Ok(unsafe { CountedPointer::from_parts(pointer.cast(), allocator) })
+                } else {
+                    Err(self)
+                }
+            }
+
+            /// Returns a reference to the value, if it has the type `T`.
+            #[inline]
+            pub fn downcast_ref<T: Any $(+ $auto_trait)*>(&self) -> Option<&T> {
+                (**self).downcast_ref()
+            }
+        }
+    };
 }
 
-impl<T: Default> Default for SharedPointer<T> {
+downcast!();
+downcast!(Send, Sync);
+
+impl<T: Default, C: Counter> Default for CountedPointer<T, Global, C> {
     #[inline]
     fn default() -> Self {
         Self::new(T::default())
     }
 }
 
-impl<T> Clone for SharedPointer<T> {
+impl<T: ?Sized, A: Allocator + Clone, C: Counter> Clone for CountedPointer<T, A, C> {
     #[inline]
     fn clone(&self) -> Self {
         // Increment the reference count
-        self.inner().1.fetch_add(1, Ordering::Relaxed);
+        self.inner().strong.increment();
 
         // Copy the pointer to a new SharedPointer and return it
-        Self(self.0)
+        Self(self.0, PhantomData, self.2.clone())
     }
 }
 
-impl<T> AsRef<T> for SharedPointer<T> {
+impl<T, C: Counter> From<Vec<T>> for CountedPointer<[T], Global, C> {
+    #[inline]
+    fn from(mut vec: Vec<T>) -> Self {
+        // Allocate a reference counter for the elements
+        let length = vec.len();
+        let pointer = Self::allocate_slice(length);
+
+        // Safety: The new allocation can store every element of the vector
+        unsafe {
+            // Move the elements from the vector to the new allocation
+            ptr::copy_nonoverlapping(
+                vec.as_ptr(),
+                ptr::addr_of_mut!((*pointer.as_ptr()).value).cast::<T>(),
+                length,
+            );
+
+            // The elements are moved, so the vector shouldn't drop them anymore
+            vec.set_len(0);
+        }
+
+        // Store the pointer in a SharedPointer and return it
+        Self(pointer, PhantomData, Global)
+    }
+}
+
+impl<T: Clone, C: Counter> From<&[T]> for CountedPointer<[T], Global, C> {
+    #[inline]
+    fn from(slice: &[T]) -> Self {
+        // Clone the elements into a vector, which drops the clones if cloning panics
+        Self::from(slice.to_vec())
+    }
+}
+
+impl<T, const N: usize, A: Allocator, C: Counter> From<CountedPointer<[T; N], A, C>>
+    for CountedPointer<[T], A, C>
+{
+    #[inline]
+    fn from(pointer: CountedPointer<[T; N], A, C>) -> Self {
+        unsize!(pointer)
+    }
+}
+
+impl<C: Counter> From<&str> for CountedPointer<str, Global, C> {
+    #[inline]
+    fn from(string: &str) -> Self {
+        // Copy the bytes of the string to a new allocation
+        Self::from_utf8(CountedPointer::<[u8], Global, C>::from(string.as_bytes()))
+    }
+}
+
+impl<C: Counter> From<String> for CountedPointer<str, Global, C> {
+    #[inline]
+    fn from(string: String) -> Self {
+        // Move the bytes of the string to a new allocation
+        Self::from_utf8(CountedPointer::<[u8], Global, C>::from(string.into_bytes()))
+    }
+}
+
+impl<C: Counter> CountedPointer<str, Global, C> {
+    /// Reinterprets bytes copied from a valid string as a string.
+    #[expect(
+        clippy::as_conversions,
+        reason = "Casting is the only way to keep the length of a pointer to an unsized struct"
+    )]
+    fn from_utf8(bytes: CountedPointer<[u8], Global, C>) -> Self {
+        // The length is stored in the pointer, so it can be cast without losing it
+        // Safety: The pointer is passed back to the rebuilder
+        let (pointer, rebuilder) = unsafe { bytes.__into_unsize_parts() };
+
+        // Safety: The bytes were taken from a valid string, so they are valid UTF-8
+        unsafe { rebuilder.__rebuild(pointer as *mut ReferenceCounter<str, C>) }
+    }
+}
+
+impl<T: ?Sized, A: Allocator, C: Counter> AsRef<T> for CountedPointer<T, A, C> {
     #[inline]
     fn as_ref(&self) -> &T {
         // Return a reference to the value stored in the reference counter
-        &self.inner().0
+        &self.inner().value
     }
 }
 
-impl<T> Deref for SharedPointer<T> {
+impl<T: ?Sized, A: Allocator, C: Counter> Deref for CountedPointer<T, A, C> {
     type Target = T;
 
     #[inline]
@@ -92,44 +849,318 @@ impl<T> Deref for SharedPointer<T> {
     }
 }
 
-impl<T: core::fmt::Debug> core::fmt::Debug for SharedPointer<T> {
+impl<T: ?Sized, A: Allocator, C: Counter> Borrow<T> for CountedPointer<T, A, C> {
+    #[inline]
+    fn borrow(&self) -> &T {
+        self.as_ref()
+    }
+}
+
+impl<T, C: Counter> From<T> for CountedPointer<T, Global, C> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: ?Sized, C: Counter> From<Box<T>> for CountedPointer<T, Global, C> {
+    #[inline]
+    #[expect(
+        clippy::as_conversions,
+        reason = "Casting is the only way to keep the metadata of a pointer to an unsized value"
+    )]
+    fn from(value: Box<T>) -> Self {
+        // The value has to be moved, as the Box has no room for the counters
+        let value_layout = Layout::for_value::<T>(&value);
+        let layout = Layout::new::<ReferenceCounter<(), C>>()
+            .extend(value_layout)
+            .expect("Value too large")
+            .0
+            .pad_to_align();
+        let memory = allocator::allocate(&Global, layout);
+
+        // Give the pointer to the new allocation the metadata of the value, like the length of a
+        // slice or the vtable of a trait object
+        let boxed = Box::into_raw(value);
+        let mut pointer = boxed as *mut ReferenceCounter<T, C>;
+        // Safety: The address is stored at the start of every pointer, followed by the metadata
+        unsafe {
+            ptr::from_mut(&mut pointer)
+                .cast::<*mut u8>()
+                .write(memory.as_ptr());
+        }
+
+        // Initialize the counters and move the value
+        // Safety: The allocation fits the counters and the value, which doesn't overlap the Box
+        unsafe {
+            ptr::addr_of_mut!((*pointer).strong).write(C::one());
+            ptr::addr_of_mut!((*pointer).weak).write(C::one());
+            ptr::copy_nonoverlapping(
+                boxed.cast::<u8>().cast_const(),
+                ptr::addr_of_mut!((*pointer).value).cast::<u8>(),
+                value_layout.size(),
+            );
+        }
+
+        // Free the Box without dropping the value, which has been moved
+        // Safety: The pointer was returned by Box::into_raw, and ManuallyDrop has the same layout
+        drop(unsafe { Box::from_raw(boxed as *mut ManuallyDrop<T>) });
+
+        // Safety: The allocation was checked for being NULL, and holds the counters and the value
+        unsafe { Self::from_parts(ptr::NonNull::new_unchecked(pointer), Global) }
+    }
+}
+
+impl<T, A: Allocator, C: Counter> From<UniquePointer<T, A>> for CountedPointer<T, A, C> {
+    #[inline]
+    fn from(pointer: UniquePointer<T, A>) -> Self {
+        // The value has to be moved, as the allocation has no room for the counters
+        let (raw, allocator) = UniquePointer::into_raw_with_allocator(pointer);
+
+        // Safety: The UniquePointer owned the value, which is moved out before freeing the memory
+        let value = unsafe {
+            let value = raw.read();
+            allocator::deallocate(
+                &allocator,
+                ptr::NonNull::new_unchecked(raw).cast(),
+                Layout::new::<T>(),
+            );
+            value
+        };
+
+        // Move the value to a new allocation, by the same allocator
+        Self::new_in(value, allocator)
+    }
+}
+
+/// Compares the values, like `Arc`.
+///
+/// Unlike `Arc`, two pointers to the same value are never considered equal without comparing the
+/// value, even if `T` is `Eq`. `Arc` can only skip the comparison through specialization, which
+/// isn't available on stable, and a separate impl for `T: Eq` would conflict with this one. Check
+/// `ptr_eq` first if comparing the values is expensive.
+impl<T: ?Sized + PartialEq, A: Allocator, C: Counter> PartialEq for CountedPointer<T, A, C> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        T::eq(self, other)
+    }
+}
+
+impl<T: ?Sized + Eq, A: Allocator, C: Counter> Eq for CountedPointer<T, A, C> {}
+
+impl<T: ?Sized + PartialOrd, A: Allocator, C: Counter> PartialOrd for CountedPointer<T, A, C> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        T::partial_cmp(self, other)
+    }
+
+    #[inline]
+    fn lt(&self, other: &Self) -> bool {
+        T::lt(self, other)
+    }
+
+    #[inline]
+    fn le(&self, other: &Self) -> bool {
+        T::le(self, other)
+    }
+
+    #[inline]
+    fn gt(&self, other: &Self) -> bool {
+        T::gt(self, other)
+    }
+
+    #[inline]
+    fn ge(&self, other: &Self) -> bool {
+        T::ge(self, other)
+    }
+}
+
+impl<T: ?Sized + Ord, A: Allocator, C: Counter> Ord for CountedPointer<T, A, C> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        T::cmp(self, other)
+    }
+}
+
+impl<T: ?Sized + Hash, A: Allocator, C: Counter> Hash for CountedPointer<T, A, C> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        T::hash(self, state);
+    }
+}
+
+impl<T: ?Sized + core::fmt::Display, A: Allocator, C: Counter> core::fmt::Display
+    for CountedPointer<T, A, C>
+{
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        T::fmt(self, f)
+    }
+}
+
+impl<T: ?Sized, A: Allocator, C: Counter> core::fmt::Pointer for CountedPointer<T, A, C> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // Format the address of the value, not of the counters
+        core::fmt::Pointer::fmt(&Self::as_ptr(self), f)
+    }
+}
+
+impl<T: ?Sized + core::fmt::Debug, A: Allocator, C: Counter> core::fmt::Debug
+    for CountedPointer<T, A, C>
+{
     #[inline]
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         // Write the SharedPointer as if the ReferenceCounter is stored in it
-        f.write_fmt(format_args!("SharedPointer({:?})", self.as_ref()))
+        f.write_fmt(format_args!("{}({:?})", C::NAME, self.as_ref()))
     }
 }
 
-impl<T> Drop for SharedPointer<T> {
+impl<T: ?Sized, A: Allocator, C: Counter> Drop for CountedPointer<T, A, C> {
     #[inline]
     fn drop(&mut self) {
         // Get a mutable reference to the ReferenceCounter
         let reference_counter = self.inner();
 
         // Decrement the reference count
-        // If the reference count is 0
-        if reference_counter.1.fetch_sub(1, Ordering::Release) <= 1 {
+        // Every use of the value by other pointers happens before the value is dropped
+        // If this was the last SharedPointer
+        if reference_counter.strong.decrement() {
             // Get the pointer
             let pointer = self.0.as_ptr();
-            // Safety: No dangling pointers are left and the pointer is not NULL
-            unsafe {
-                // Call the destructor of the pointed to value
-                pointer.drop_in_place();
 
-                // Free the memory
-                alloc::alloc::dealloc(pointer.cast(), Layout::new::<ReferenceCounter<T>>());
-            };
+            // Call the destructor of the pointed to value
+            // Safety: No SharedPointers are left, so the value can't be accessed anymore
+            unsafe { ptr::addr_of_mut!((*pointer).value).drop_in_place() }
+
+            // Release the weak reference shared by all SharedPointers
+            drop(CountedWeakPointer(self.0, &self.2));
+        }
+    }
+}
+
+/// A pointer to the value of a `SharedPointer`, which doesn't keep the value alive.
+///
+/// The memory is only freed once both all `SharedPointer`s and all `WeakPointer`s are dropped.
+pub struct CountedWeakPointer<T: ?Sized, A: Allocator = Global, C: Counter = AtomicCounter>(
+    ptr::NonNull<ReferenceCounter<T, C>>,
+    A,
+);
+
+/// A pointer to the value of a `SharedPointer`, which doesn't keep the value alive.
+pub type WeakPointer<T, A = Global> = CountedWeakPointer<T, A, AtomicCounter>;
+
+/// A pointer to the value of a `LocalSharedPointer`, which doesn't keep the value alive.
+pub type LocalWeakPointer<T, A = Global> = CountedWeakPointer<T, A, LocalCounter>;
+
+/// Safety:
+/// Counters are atomic and the value can only be accessed through an upgraded `SharedPointer`.
+unsafe impl<T: ?Sized + Send + Sync, A: Allocator + Send, C: Counter + Sync> Send
+    for CountedWeakPointer<T, A, C>
+{
+}
+
+/// Safety:
+/// Counters are atomic and the value can only be accessed through an upgraded `SharedPointer`.
+unsafe impl<T: ?Sized + Send + Sync, A: Allocator + Sync, C: Counter + Sync> Sync
+    for CountedWeakPointer<T, A, C>
+{
+}
+
+impl<T: ?Sized, A: Allocator, C: Counter> CountedWeakPointer<T, A, C> {
+    /// Returns the number of `SharedPointer`s to the value.
+    #[inline]
+    pub fn reference_count(&self) -> usize {
+        self.inner().strong.count()
+    }
+
+    /// Returns the number of `WeakPointer`s to the value, or 0 if the value was dropped.
+    #[inline]
+    pub fn weak_count(&self) -> usize {
+        if self.reference_count() == 0 {
+            0
+        } else {
+            // Subtract the weak reference shared by all `SharedPointer`s
+            self.inner().weak.count().saturating_sub(1)
+        }
+    }
+
+    const fn inner(&self) -> &ReferenceCounter<T, C> {
+        // Safety: The memory isn't freed as long as a WeakPointer to it exists
+        unsafe { self.0.as_ref() }
+    }
+
+    /// Splits the `WeakPointer` into the pointer and the allocator, without changing the count.
+    fn into_parts(self) -> (ptr::NonNull<ReferenceCounter<T, C>>, A) {
+        // The reference is now owned by the returned pointer, so the count shouldn't change
+        let pointer = ManuallyDrop::new(self);
+
+        // Safety: The allocator is moved out of a pointer that will never be used again
+        let allocator = unsafe { ptr::read(&raw const pointer.1) };
+        (pointer.0, allocator)
+    }
+}
+
+impl<T: ?Sized, A: Allocator + Clone, C: Counter> CountedWeakPointer<T, A, C> {
+    /// Tries to create a `SharedPointer` to the value, returns `None` if it was already dropped.
+    #[inline]
+    pub fn upgrade(&self) -> Option<CountedPointer<T, A, C>> {
+        // Increment the reference count, unless the value has already been dropped
+        // Copy the pointer to a new SharedPointer and return it
+        self.inner()
+            .strong
+            .increment_nonzero()
+            .then(|| CountedPointer(self.0, PhantomData, self.1.clone()))
+    }
+}
+
+impl<T: ?Sized, A: Allocator + Clone, C: Counter> Clone for CountedWeakPointer<T, A, C> {
+    #[inline]
+    fn clone(&self) -> Self {
+        // Increment the weak reference count
+        self.inner().weak.increment();
+
+        // Copy the pointer to a new WeakPointer and return it
+        Self(self.0, self.1.clone())
+    }
+}
+
+impl<T: ?Sized, A: Allocator, C: Counter> core::fmt::Debug for CountedWeakPointer<T, A, C> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // The value may already be dropped, so it can't be printed
+        f.write_fmt(format_args!("({})", C::WEAK_NAME))
+    }
+}
+
+impl<T: ?Sized, A: Allocator, C: Counter> Drop for CountedWeakPointer<T, A, C> {
+    #[inline]
+    fn drop(&mut self) {
+        // Decrement the weak reference count
+        // If this was the last reference to the allocation
+        // Every access to the allocation happens before it is freed
+        if self.inner().weak.decrement() {
+            // Get the layout of the allocation, the size of the value is stored in the pointer
+            let layout = Layout::for_value(self.inner());
+
+            // Free the memory, the value has already been dropped by the last SharedPointer
+            // Safety: No pointers to the allocation are left
+            unsafe { allocator::deallocate(&self.1, self.0.cast(), layout) }
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use core::{cell::RefCell, fmt::Write, sync::atomic::Ordering};
+    use core::{cell::RefCell, fmt::Write as _};
 
     use heapless::String;
 
-    use super::SharedPointer;
+    use super::{value_offset, CountedPointer, LocalSharedPointer, SharedPointer};
+    use crate::{
+        counter::{AtomicCounter, AtomicCounter32, Counter as _},
+        Global,
+    };
 
     #[test]
     fn pointer_creation() {
@@ -186,7 +1217,7 @@ mod tests {
         let pointer = SharedPointer::new(value);
 
         // Get the reference count
-        let mut reference_count = pointer.inner().1.load(Ordering::Relaxed);
+        let mut reference_count = pointer.inner().strong.count();
 
         // Check whether it is 1
         assert_eq!(reference_count, 1);
@@ -199,14 +1230,14 @@ mod tests {
             assert_eq!(pointer.0, cloned_pointer.0);
 
             // Get the reference count
-            reference_count = pointer.inner().1.load(Ordering::Relaxed);
+            reference_count = pointer.inner().strong.count();
 
             // Check whether the reference count is 2
             assert_eq!(reference_count, 2);
         }
 
         // Get the reference count
-        reference_count = pointer.inner().1.load(Ordering::Relaxed);
+        reference_count = pointer.inner().strong.count();
 
         // Check whether the reference count is 1
         assert_eq!(reference_count, 1);
@@ -231,4 +1262,126 @@ mod tests {
         // Check whether the pointer is formatted as expected
         assert_eq!(debug_output, expected_output);
     }
+
+    #[test]
+    fn weak_counting() {
+        // Store a value in a SharedPointer
+        let pointer = SharedPointer::new(rand::random::<u32>());
+
+        // Check whether only the shared weak reference exists
+        assert_eq!(pointer.inner().weak.count(), 1);
+        {
+            // Create a WeakPointer
+            let weak_pointer = pointer.downgrade();
+
+            // Check whether it points to the same allocation
+            assert_eq!(pointer.0, weak_pointer.0);
+
+            // Check whether the weak reference count was incremented
+            assert_eq!(pointer.inner().weak.count(), 2);
+            assert_eq!(pointer.weak_count(), 1);
+        }
+
+        // Check whether the weak reference count was decremented
+        assert_eq!(pointer.inner().weak.count(), 1);
+        assert_eq!(pointer.weak_count(), 0);
+    }
+
+    #[test]
+    fn raw_pointer_offset() {
+        // A value with a higher alignment than the counters is stored after padding
+        #[repr(align(64))]
+        struct Aligned;
+
+        // The reference counter should be found again from a pointer to the value
+        let pointer = SharedPointer::new(Aligned);
+        let raw = SharedPointer::as_ptr(&pointer);
+
+        // Safety: The pointer points to a live value
+        let counter = unsafe { SharedPointer::<Aligned>::counter_from_raw(raw) };
+        assert_eq!(counter, pointer.0);
+        assert_eq!(raw.addr() - pointer.0.addr().get(), 64);
+    }
+
+    #[test]
+    fn local_cloning() {
+        // Store a random value in a LocalSharedPointer
+        let value = rand::random::<f64>();
+        let pointer = LocalSharedPointer::new(value);
+
+        // Check whether the reference count is 1
+        assert_eq!(pointer.inner().strong.count(), 1);
+        {
+            // Clone the LocalSharedPointer
+            let cloned_pointer = pointer.clone();
+
+            // Check whether the values and pointers are the same
+            assert_eq!(*pointer, *cloned_pointer);
+            assert_eq!(pointer.0, cloned_pointer.0);
+
+            // Check whether the reference count is 2
+            assert_eq!(pointer.inner().strong.count(), 2);
+        }
+
+        // Check whether the reference count is 1 again
+        assert_eq!(pointer.inner().strong.count(), 1);
+    }
+
+    #[test]
+    fn local_debug() {
+        // Store a random value in a LocalSharedPointer
+        let value = rand::random::<u8>();
+        let pointer = LocalSharedPointer::new(value);
+
+        // Write the debug format to a stack String
+        let mut debug_output = String::<64>::new();
+        write!(debug_output, "{pointer:?}").unwrap();
+
+        // Write the format we expect to a stack String
+        let mut expected_output = String::<32>::new();
+        write!(expected_output, "LocalSharedPointer({value})").unwrap();
+
+        // Check whether the pointer is formatted as expected
+        assert_eq!(debug_output, expected_output);
+    }
+
+    #[test]
+    fn local_weak_counting() {
+        // Store a value in a LocalSharedPointer
+        let pointer = LocalSharedPointer::new(rand::random::<u32>());
+
+        // Check whether only the shared weak reference exists
+        assert_eq!(pointer.inner().weak.count(), 1);
+        {
+            // Create a LocalWeakPointer
+            let weak_pointer = pointer.downgrade();
+
+            // Check whether it points to the same allocation
+            assert_eq!(pointer.0, weak_pointer.0);
+
+            // Check whether the weak reference count was incremented
+            assert_eq!(pointer.inner().weak.count(), 2);
+            assert_eq!(pointer.weak_count(), 1);
+        }
+
+        // Check whether the weak reference count was decremented
+        assert_eq!(pointer.inner().weak.count(), 1);
+        assert_eq!(pointer.weak_count(), 0);
+    }
+
+    #[test]
+    fn counter_size() {
+        // 32-bit counters make the value start earlier on 64-bit targets
+        assert_eq!(value_offset::<AtomicCounter>(4), 2 * size_of::<usize>());
+        assert_eq!(value_offset::<AtomicCounter32>(4), 8);
+
+        // The value is still found again from a pointer to it
+        let pointer = CountedPointer::<u32, Global, AtomicCounter32>::new(rand::random());
+        let raw = CountedPointer::as_ptr(&pointer);
+
+        // Safety: The pointer points to a live value
+        let counter =
+            unsafe { CountedPointer::<u32, Global, AtomicCounter32>::counter_from_raw(raw) };
+        assert_eq!(counter, pointer.0);
+    }
 }
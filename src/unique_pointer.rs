@@ -1,42 +1,606 @@
 use core::{
     alloc::Layout,
+    any::Any,
+    borrow::{Borrow, BorrowMut},
+    cmp::Ordering,
+    convert::Infallible,
+    future::Future,
+    hash::{Hash, Hasher},
+    iter::FusedIterator,
+    marker::PhantomData,
+    mem::{ManuallyDrop, MaybeUninit},
     ops::{Deref, DerefMut},
+    panic::UnwindSafe,
+    pin::Pin,
     ptr,
+    str::{self, FromStr},
+    task::{Context, Poll},
 };
 
-use alloc::borrow::ToOwned;
+use alloc::{borrow::ToOwned as _, boxed::Box, string::String, vec::Vec};
+
+use crate::{
+    allocator::{self, AllocError, Allocator, Global},
+    init::{Initialized, Slot},
+};
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "std")]
+use std::io;
 
-pub struct UniquePointer<T>(ptr::NonNull<T>);
+/// A pointer to a value it owns, which is dropped with the pointer.
+///
+/// Closures behind a `UniquePointer` can be called directly, like with a `Box`. A `dyn FnOnce` has
+/// to be moved out of the allocation to be called, which stable Rust only allows for a `Box`, so
+/// it's converted first:
+/// ```
+/// use smart_pointers::{unsize, UniquePointer};
+///
+/// let mut count = 0;
+/// let mut increment: UniquePointer<dyn FnMut() -> i32> = unsize!(UniquePointer::new(|| {
+///     count += 1;
+///     count
+/// }));
+/// assert_eq!(increment(), 1);
+/// assert_eq!(increment(), 2);
+///
+/// let finish: UniquePointer<dyn FnOnce() -> String> =
+///     unsize!(UniquePointer::new(|| String::from("done")));
+/// assert_eq!(UniquePointer::into_box(finish)(), "done");
+/// ```
+///
+/// The `PhantomData` tells the drop checker that the `UniquePointer` owns and drops a `T`.
+///
+/// The memory is allocated by `A`, which is stored in the `UniquePointer` to free it again.
+///
+/// Functions like `UniquePointer::into_box` are associated functions instead of methods, so they
+/// don't hide methods of the value.
+///
+/// Pinning requires a `'static` allocator, so the memory can't be reused without dropping the
+/// value.
+pub struct UniquePointer<T: ?Sized, A: Allocator = Global>(ptr::NonNull<T>, PhantomData<T>, A);
 
 /// Safety: Each `UniquePointer` points to a different piece of memory.
-unsafe impl<T: Send> Send for UniquePointer<T> {}
+unsafe impl<T: ?Sized + Send, A: Allocator + Send> Send for UniquePointer<T, A> {}
 
-impl<T> UniquePointer<T> {
-    fn allocate_memory() -> ptr::NonNull<T> {
-        // Allocate memory
-        // Safety: Pointer will be checked for NULL before usage.
-        let pointer = unsafe { alloc::alloc::alloc(Layout::new::<T>()) };
+/// Safety: Shared access to the `UniquePointer` only gives shared access to the value.
+unsafe impl<T: ?Sized + Sync, A: Allocator + Sync> Sync for UniquePointer<T, A> {}
+
+/// Moving the `UniquePointer` doesn't move the value, so it doesn't matter whether `T` is `Unpin`.
+impl<T: ?Sized, A: Allocator> Unpin for UniquePointer<T, A> {}
+
+/// The `UniquePointer` owns the value like `Box`, so the value only has to be `UnwindSafe` itself.
+impl<T: ?Sized + UnwindSafe, A: Allocator + UnwindSafe> UnwindSafe for UniquePointer<T, A> {}
+
+/// Rebuilds a `UniquePointer` from a coerced pointer, used by the `unsize` macro.
+#[doc(hidden)]
+pub struct UniqueRebuilder<A>(A);
+
+impl<A: Allocator> UniqueRebuilder<A> {
+    /// # Safety
+    /// The pointer has to be taken from a `UniquePointer` by `__into_unsize_parts`, together with
+    /// this rebuilder, only coercions are allowed before passing it.
+    #[inline]
+    pub unsafe fn __rebuild<T: ?Sized>(self, pointer: *mut T) -> UniquePointer<T, A> {
+        // Safety: The pointer was taken from a UniquePointer, so it can't be NULL
+        let non_null = unsafe { ptr::NonNull::new_unchecked(pointer) };
+
+        // Safety: A coercion keeps the pointer pointing to the same value
+        unsafe { UniquePointer::from_parts(non_null, self.0) }
+    }
+}
+
+impl<T: ?Sized, A: Allocator> UniquePointer<T, A> {
+    /// Returns the allocator the value is stored in.
+    #[inline]
+    pub const fn allocator(&self) -> &A {
+        &self.2
+    }
+
+    /// Returns a pointer to the value, which stays valid until the `UniquePointer` is dropped.
+    #[inline]
+    pub const fn as_ptr(pointer: &Self) -> *const T {
+        pointer.0.as_ptr()
+    }
+
+    /// Returns a mutable pointer to the value, which stays valid until the `UniquePointer` is
+    /// dropped.
+    #[inline]
+    pub const fn as_mut_ptr(pointer: &mut Self) -> *mut T {
+        pointer.0.as_ptr()
+    }
+
+    /// Converts the `UniquePointer` to a pointer to the value and its allocator, the value is kept
+    /// alive until the pointer is passed to `from_raw_in`.
+    #[inline]
+    pub fn into_raw_with_allocator(pointer: Self) -> (*mut T, A) {
+        let (raw, allocator) = pointer.into_parts();
+        (raw.as_ptr(), allocator)
+    }
+
+    /// Converts a pointer returned by `into_raw_with_allocator` back to a `UniquePointer`.
+    ///
+    /// # Safety
+    /// The pointer has to be returned by `into_raw` or `into_raw_with_allocator` of a
+    /// `UniquePointer<T, A>`, and the allocator has to be the one returned with it. Every pointer
+    /// may only be converted back once, as the `UniquePointer` frees the value when it's dropped.
+    #[inline]
+    pub const unsafe fn from_raw_in(pointer: *mut T, allocator: A) -> Self {
+        // Safety: The caller guarantees the pointer was taken from a UniquePointer, so it can't be
+        // NULL
+        let non_null = unsafe { ptr::NonNull::new_unchecked(pointer) };
+
+        // Safety: The caller guarantees the value is still owned by the pointer
+        unsafe { Self::from_parts(non_null, allocator) }
+    }
+
+    /// Leaks the value, so it's never dropped and can be used for the rest of the program.
+    #[inline]
+    pub fn leak<'value>(pointer: Self) -> &'value mut T
+    where
+        A: 'value,
+    {
+        // The allocator is leaked as well, so it can't be dropped while the memory is in use
+        let mut leaked = ManuallyDrop::new(pointer);
+
+        // Safety: The value is never freed, and the leaked pointer can't access it anymore
+        unsafe { leaked.0.as_mut() }
+    }
+
+    /// Pins the value, it can't be moved out of the returned pointer anymore.
+    #[inline]
+    pub const fn into_pin(pointer: Self) -> Pin<Self>
+    where
+        A: 'static,
+    {
+        // Safety: The value is never moved by the UniquePointer, and can't be accessed anymore
+        // without going through the Pin
+        unsafe { Pin::new_unchecked(pointer) }
+    }
+
+    /// Splits the `UniquePointer` into a pointer that can be coerced, used by the `unsize` macro.
+    ///
+    /// # Safety
+    /// The pointer may only be passed to `__rebuild` of the returned rebuilder, after an unsizing
+    /// coercion, otherwise the value is leaked.
+    #[doc(hidden)]
+    #[inline]
+    pub unsafe fn __into_unsize_parts(self) -> (*mut T, UniqueRebuilder<A>) {
+        let (pointer, allocator) = self.into_parts();
+        (pointer.as_ptr(), UniqueRebuilder(allocator))
+    }
+
+    /// Splits the `UniquePointer` into the pointer and the allocator, without freeing the value.
+    pub(crate) fn into_parts(self) -> (ptr::NonNull<T>, A) {
+        // The memory is now owned by the returned pointer, so it shouldn't be freed
+        let pointer = ManuallyDrop::new(self);
+
+        // Safety: The allocator is moved out of a pointer that will never be used again
+        let allocator = unsafe { ptr::read(&raw const pointer.2) };
+        (pointer.0, allocator)
+    }
+
+    /// Creates a `UniquePointer` from a pointer and the allocator it was allocated by.
+    ///
+    /// # Safety
+    /// The pointer has to point to an initialized value, in memory allocated by the allocator for
+    /// the layout of that value.
+    pub(crate) const unsafe fn from_parts(pointer: ptr::NonNull<T>, allocator: A) -> Self {
+        Self(pointer, PhantomData, allocator)
+    }
+}
 
-        // Store the pointer in a non-null pointer and return it
-        ptr::NonNull::<T>::new(pointer.cast()).expect("No memory")
+impl<T: ?Sized> UniquePointer<T> {
+    /// Converts the `UniquePointer` to a pointer to the value, the value is kept alive until the
+    /// pointer is passed to `from_raw`.
+    #[inline]
+    pub fn into_raw(pointer: Self) -> *mut T {
+        Self::into_raw_with_allocator(pointer).0
+    }
+
+    /// Converts the `UniquePointer` to a `Box`, without moving the value.
+    #[inline]
+    pub fn into_box(pointer: Self) -> Box<T> {
+        // Safety: Both use the global allocator and the layout of the value, and dangling pointers
+        // for zero-sized values
+        unsafe { Box::from_raw(Self::into_raw(pointer)) }
+    }
+
+    /// Converts a `Box` to a `UniquePointer`, without moving the value.
+    ///
+    /// This isn't a `From` implementation, as it would conflict with converting every error to a
+    /// `UniquePointer<dyn Error>`.
+    #[inline]
+    pub fn from_box(boxed: Box<T>) -> Self {
+        // Safety: Both use the global allocator and the layout of the value, and dangling pointers
+        // for zero-sized values
+        unsafe { Self::from_raw(Box::into_raw(boxed)) }
+    }
+
+    /// Converts a pointer returned by `into_raw` back to a `UniquePointer`.
+    ///
+    /// # Safety
+    /// The pointer has to be returned by `into_raw` of a `UniquePointer<T>`. Every pointer may only
+    /// be converted back once, as the `UniquePointer` frees the value when it's dropped.
+    #[inline]
+    pub const unsafe fn from_raw(pointer: *mut T) -> Self {
+        // Safety: The caller guarantees the pointer was returned by into_raw
+        unsafe { Self::from_raw_in(pointer, Global) }
     }
+}
 
+impl<T> UniquePointer<T> {
     #[inline]
     pub fn new(value: T) -> Self {
+        Self::new_in(value, Global)
+    }
+
+    /// Stores the value in a new `UniquePointer`, without panicking if no memory could be
+    /// allocated.
+    ///
+    /// # Errors
+    /// Returns an `AllocError` if no memory could be allocated.
+    #[inline]
+    pub fn try_new(value: T) -> Result<Self, AllocError> {
+        Self::try_new_in(value, Global)
+    }
+
+    /// Stores the value in a new pinned `UniquePointer`, so it can never be moved again.
+    #[inline]
+    pub fn pin(value: T) -> Pin<Self> {
+        Self::pin_in(value, Global)
+    }
+
+    /// Initializes the value directly in the allocated memory, so it never has to be moved.
+    ///
+    /// The pointer isn't pinned, so a value that stores its own address should use `pin_with`.
+    #[inline]
+    pub fn new_with<F>(init: F) -> Self
+    where
+        F: for<'slot> FnOnce(Slot<'slot, T>) -> Initialized<'slot, T>,
+    {
+        Self::new_with_in(init, Global)
+    }
+
+    /// Initializes the value directly in the allocated memory, the memory is freed again if the
+    /// initialization fails.
+    ///
+    /// # Errors
+    /// Returns the error of the initialization.
+    #[inline]
+    pub fn try_new_with<E, F>(init: F) -> Result<Self, E>
+    where
+        F: for<'slot> FnOnce(Slot<'slot, T>) -> Result<Initialized<'slot, T>, E>,
+    {
+        Self::try_new_with_in(init, Global)
+    }
+
+    /// Initializes the value directly in the allocated memory and pins it, so a value that stores
+    /// its own address can never be moved.
+    #[inline]
+    pub fn pin_with<F>(init: F) -> Pin<Self>
+    where
+        F: for<'slot> FnOnce(Slot<'slot, T>) -> Initialized<'slot, T>,
+    {
+        Self::pin_with_in(init, Global)
+    }
+
+    /// Initializes the value directly in the allocated memory and pins it, the memory is freed
+    /// again if the initialization fails.
+    ///
+    /// # Errors
+    /// Returns the error of the initialization.
+    #[inline]
+    pub fn try_pin_with<E, F>(init: F) -> Result<Pin<Self>, E>
+    where
+        F: for<'slot> FnOnce(Slot<'slot, T>) -> Result<Initialized<'slot, T>, E>,
+    {
+        Self::try_pin_with_in(init, Global)
+    }
+
+    /// Allocates memory for a value, without initializing it.
+    #[inline]
+    pub fn new_uninit() -> UniquePointer<MaybeUninit<T>> {
+        Self::new_uninit_in(Global)
+    }
+
+    /// Allocates memory for a value, with every byte set to 0.
+    #[inline]
+    pub fn new_zeroed() -> UniquePointer<MaybeUninit<T>> {
+        Self::new_zeroed_in(Global)
+    }
+
+    /// Allocates memory for a slice, without initializing the elements.
+    ///
+    /// # Panics
+    /// Panics if the slice is larger than `isize::MAX` bytes.
+    #[inline]
+    pub fn new_uninit_slice(length: usize) -> UniquePointer<[MaybeUninit<T>]> {
+        Self::new_uninit_slice_in(length, Global)
+    }
+
+    /// Allocates memory for a slice, with every byte set to 0.
+    ///
+    /// # Panics
+    /// Panics if the slice is larger than `isize::MAX` bytes.
+    #[inline]
+    pub fn new_zeroed_slice(length: usize) -> UniquePointer<[MaybeUninit<T>]> {
+        Self::new_zeroed_slice_in(length, Global)
+    }
+}
+
+impl<T, A: Allocator> UniquePointer<T, A> {
+    /// Stores the value in a new `UniquePointer`, using memory from the allocator.
+    #[inline]
+    pub fn new_in(value: T, allocator: A) -> Self {
+        Self::try_new_in(value, allocator)
+            .unwrap_or_else(|AllocError| alloc::alloc::handle_alloc_error(Layout::new::<T>()))
+    }
+
+    /// Stores the value in a new `UniquePointer`, using memory from the allocator, without
+    /// panicking if no memory could be allocated.
+    ///
+    /// # Errors
+    /// Returns an `AllocError` if no memory could be allocated.
+    #[inline]
+    pub fn try_new_in(value: T, allocator: A) -> Result<Self, AllocError> {
         // Allocate memory
-        let pointer = Self::allocate_memory();
+        allocator::try_allocate(&allocator, Layout::new::<T>()).map(|memory| {
+            let pointer = memory.cast::<T>();
+
+            // Store the value at the address pointed to by the pointer
+            // Safety: Pointer can't be NULL
+            unsafe { pointer.as_ptr().write(value) }
+
+            // Store the pointer in a UniquePointer and return it
+            Self(pointer, PhantomData, allocator)
+        })
+    }
+
+    /// Stores the value in a new pinned `UniquePointer`, using memory from the allocator.
+    #[inline]
+    pub fn pin_in(value: T, allocator: A) -> Pin<Self>
+    where
+        A: 'static,
+    {
+        Self::into_pin(Self::new_in(value, allocator))
+    }
+
+    /// Initializes the value directly in memory from the allocator, so it never has to be moved.
+    #[inline]
+    pub fn new_with_in<F>(init: F, allocator: A) -> Self
+    where
+        F: for<'slot> FnOnce(Slot<'slot, T>) -> Initialized<'slot, T>,
+    {
+        Self::try_new_with_in(|slot| Ok::<_, Infallible>(init(slot)), allocator)
+            .unwrap_or_else(|never| match never {})
+    }
+
+    /// Initializes the value directly in memory from the allocator, the memory is freed again if
+    /// the initialization fails.
+    ///
+    /// # Errors
+    /// Returns the error of the initialization.
+    #[inline]
+    pub fn try_new_with_in<E, F>(init: F, allocator: A) -> Result<Self, E>
+    where
+        F: for<'slot> FnOnce(Slot<'slot, T>) -> Result<Initialized<'slot, T>, E>,
+    {
+        // The uninitialized pointer frees the memory if the initialization fails or panics
+        let mut pointer = Self::new_uninit_in(allocator);
+        init(Slot::new(&mut pointer))
+            .map(Initialized::finish)
+            .map(|()| {
+                // Safety: The Initialized proves that the value was initialized
+                unsafe { pointer.assume_init() }
+            })
+    }
+
+    /// Initializes the value directly in memory from the allocator and pins it, so a value that
+    /// stores its own address can never be moved.
+    #[inline]
+    pub fn pin_with_in<F>(init: F, allocator: A) -> Pin<Self>
+    where
+        A: 'static,
+        F: for<'slot> FnOnce(Slot<'slot, T>) -> Initialized<'slot, T>,
+    {
+        Self::into_pin(Self::new_with_in(init, allocator))
+    }
+
+    /// Initializes the value directly in memory from the allocator and pins it, the memory is freed
+    /// again if the initialization fails.
+    ///
+    /// # Errors
+    /// Returns the error of the initialization.
+    #[inline]
+    pub fn try_pin_with_in<E, F>(init: F, allocator: A) -> Result<Pin<Self>, E>
+    where
+        A: 'static,
+        F: for<'slot> FnOnce(Slot<'slot, T>) -> Result<Initialized<'slot, T>, E>,
+    {
+        Self::try_new_with_in(init, allocator).map(Self::into_pin)
+    }
+
+    /// Allocates memory for a value from the allocator, without initializing it.
+    #[inline]
+    pub fn new_uninit_in(allocator: A) -> UniquePointer<MaybeUninit<T>, A> {
+        let pointer = allocator::allocate(&allocator, Layout::new::<T>());
+
+        // Safety: Uninitialized memory is a valid MaybeUninit
+        unsafe { UniquePointer::from_parts(pointer.cast(), allocator) }
+    }
+
+    /// Allocates memory for a value from the allocator, with every byte set to 0.
+    #[inline]
+    pub fn new_zeroed_in(allocator: A) -> UniquePointer<MaybeUninit<T>, A> {
+        let pointer = allocator::allocate_zeroed(&allocator, Layout::new::<T>());
+
+        // Safety: Zeroed memory is a valid MaybeUninit
+        unsafe { UniquePointer::from_parts(pointer.cast(), allocator) }
+    }
+
+    /// Allocates memory for a slice from the allocator, without initializing the elements.
+    ///
+    /// # Panics
+    /// Panics if the slice is larger than `isize::MAX` bytes.
+    #[inline]
+    pub fn new_uninit_slice_in(length: usize, allocator: A) -> UniquePointer<[MaybeUninit<T>], A> {
+        let layout = Layout::array::<T>(length).expect("Slice too large");
+        let pointer = allocator::allocate(&allocator, layout).cast();
+
+        // Safety: Uninitialized memory is a valid slice of MaybeUninit
+        unsafe {
+            UniquePointer::from_parts(
+                ptr::NonNull::slice_from_raw_parts(pointer, length),
+                allocator,
+            )
+        }
+    }
+
+    /// Allocates memory for a slice from the allocator, with every byte set to 0.
+    ///
+    /// # Panics
+    /// Panics if the slice is larger than `isize::MAX` bytes.
+    #[inline]
+    pub fn new_zeroed_slice_in(length: usize, allocator: A) -> UniquePointer<[MaybeUninit<T>], A> {
+        let layout = Layout::array::<T>(length).expect("Slice too large");
+        let pointer = allocator::allocate_zeroed(&allocator, layout).cast();
+
+        // Safety: Zeroed memory is a valid slice of MaybeUninit
+        unsafe {
+            UniquePointer::from_parts(
+                ptr::NonNull::slice_from_raw_parts(pointer, length),
+                allocator,
+            )
+        }
+    }
+}
+
+impl<T, A: Allocator> UniquePointer<MaybeUninit<T>, A> {
+    /// Converts the pointer to a pointer to the initialized value, without copying it.
+    ///
+    /// # Safety
+    /// The value has to be initialized.
+    #[inline]
+    pub unsafe fn assume_init(self) -> UniquePointer<T, A> {
+        let (pointer, allocator) = self.into_parts();
+
+        // Safety: The caller guarantees the value is initialized
+        unsafe { UniquePointer::from_parts(pointer.cast(), allocator) }
+    }
 
-        // Store the value at the address pointed to by the pointer
-        // Safety: Pointer can't be NULL
-        unsafe { pointer.as_ptr().write(value) }
+    /// Stores the value in the allocated memory and returns a pointer to it.
+    ///
+    /// This is an associated function, so it doesn't hide `MaybeUninit::write`.
+    #[expect(
+        clippy::same_name_method,
+        reason = "Like `Box::write`, it can't be confused with `Hasher::write`, as it takes no self"
+    )]
+    #[inline]
+    pub fn write(mut pointer: Self, value: T) -> UniquePointer<T, A> {
+        pointer.as_mut().write(value);
+
+        // Safety: The value was just initialized
+        unsafe { pointer.assume_init() }
+    }
+}
+
+impl<T, A: Allocator> UniquePointer<[MaybeUninit<T>], A> {
+    /// Converts the pointer to a pointer to the initialized elements, without copying them.
+    ///
+    /// # Safety
+    /// Every element has to be initialized.
+    #[inline]
+    pub unsafe fn assume_init(self) -> UniquePointer<[T], A> {
+        let (pointer, allocator) = self.into_parts();
 
-        // Store the pointer in a UniquePointer and return it
-        Self(pointer)
+        // Safety: The caller guarantees the elements are initialized, the length stays the same
+        unsafe {
+            UniquePointer::from_parts(
+                ptr::NonNull::slice_from_raw_parts(pointer.cast(), pointer.len()),
+                allocator,
+            )
+        }
     }
 }
 
+impl<T, A: Allocator> UniquePointer<[T], A> {
+    /// Moves the elements of the vector to a slice, using memory from the allocator.
+    fn from_vec_in(mut vec: Vec<T>, allocator: A) -> Self {
+        // Allocate memory for the elements
+        let length = vec.len();
+        let layout = Layout::array::<T>(length).expect("Slice too large");
+        let pointer = allocator::allocate(&allocator, layout).cast::<T>();
+
+        // Safety: The new allocation can store every element of the vector
+        unsafe {
+            // Move the elements from the vector to the new allocation
+            ptr::copy_nonoverlapping(vec.as_ptr(), pointer.as_ptr(), length);
+
+            // The elements are moved, so the vector shouldn't drop them anymore
+            vec.set_len(0);
+        }
+
+        // Store the pointer to the slice in a UniquePointer and return it
+        Self(
+            ptr::NonNull::slice_from_raw_parts(pointer, length),
+            PhantomData,
+            allocator,
+        )
+    }
+}
+
+impl<A: Allocator> UniquePointer<str, A> {
+    /// Moves the bytes of a string to a new allocation.
+    fn from_string_in(string: String, allocator: A) -> Self {
+        // Move the bytes of the string to a new allocation
+        let bytes = UniquePointer::from_vec_in(string.into_bytes(), allocator);
+
+        // Reinterpret the bytes as a string, the length is stored in the pointer
+        // Safety: The pointer is passed back to the rebuilder
+        let (pointer, rebuilder) = unsafe { bytes.__into_unsize_parts() };
+
+        // Safety: The bytes were moved from a valid string, so they are valid UTF-8
+        unsafe { rebuilder.__rebuild(ptr::from_mut(str::from_utf8_unchecked_mut(&mut *pointer))) }
+    }
+}
+
+/// Implements downcasting for a `UniquePointer` to a `dyn Any` with the given auto traits.
+macro_rules! downcast {
+    ($($auto_trait:ident),*) => {
+        impl<A: Allocator> UniquePointer<dyn Any $(+ $auto_trait)*, A> {
+            /// Converts the pointer to a pointer to the concrete type, without moving the value.
+            ///
+            /// # Errors
+            /// Returns the pointer if the value has a different type.
+            #[inline]
+            pub fn downcast<T: Any>(self) -> Result<UniquePointer<T, A>, Self> {
+                if (*self).is::<T>() {
+                    let (pointer, allocator) = self.into_parts();
+
+                    // Safety: The value has the type T, and stays in the same allocation
+                    Ok(unsafe { UniquePointer::from_parts(pointer.cast(), allocator) })
+                } else {
+                    Err(self)
+                }
+            }
+
+            /// Returns a reference to the value, if it has the type `T`.
+            #[inline]
+            pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+                (**self).downcast_ref()
+            }
+        }
+    };
+}
+
+downcast!();
+downcast!(Send);
+downcast!(Send, Sync);
+
 impl<T: Default> Default for UniquePointer<T> {
     #[inline]
     fn default() -> Self {
@@ -44,15 +608,75 @@ impl<T: Default> Default for UniquePointer<T> {
     }
 }
 
-impl<T: Clone> Clone for UniquePointer<T> {
+impl<T: Clone, A: Allocator + Clone> Clone for UniquePointer<T, A> {
     #[inline]
     fn clone(&self) -> Self {
         // Clone the value stored in the UniquePointer and use it to create a new one
-        Self::new(self.deref().to_owned())
+        Self::new_in(self.deref().to_owned(), self.2.clone())
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone> Clone for UniquePointer<[T], A> {
+    #[inline]
+    fn clone(&self) -> Self {
+        // Clone the elements into a vector, which drops the clones if cloning panics
+        Self::from_vec_in(self.to_vec(), self.2.clone())
+    }
+}
+
+impl<A: Allocator + Clone> Clone for UniquePointer<str, A> {
+    #[inline]
+    fn clone(&self) -> Self {
+        // Copy the string stored in the UniquePointer and use it to create a new one
+        Self::from_string_in(String::from(self.as_ref()), self.2.clone())
     }
 }
 
-impl<T> AsRef<T> for UniquePointer<T> {
+impl<T> From<Vec<T>> for UniquePointer<[T]> {
+    #[inline]
+    fn from(vec: Vec<T>) -> Self {
+        Self::from_vec_in(vec, Global)
+    }
+}
+
+impl<T: Clone> From<&[T]> for UniquePointer<[T]> {
+    #[inline]
+    fn from(slice: &[T]) -> Self {
+        // Clone the elements into a vector, which drops the clones if cloning panics
+        Self::from(slice.to_vec())
+    }
+}
+
+impl<T, const N: usize, A: Allocator> From<UniquePointer<[T; N], A>> for UniquePointer<[T], A> {
+    #[inline]
+    fn from(pointer: UniquePointer<[T; N], A>) -> Self {
+        unsize!(pointer)
+    }
+}
+
+impl From<&str> for UniquePointer<str> {
+    #[inline]
+    fn from(string: &str) -> Self {
+        // Copy the string to a new allocation
+        Self::from_string_in(String::from(string), Global)
+    }
+}
+
+impl From<String> for UniquePointer<str> {
+    #[inline]
+    fn from(string: String) -> Self {
+        Self::from_string_in(string, Global)
+    }
+}
+
+impl<T: ?Sized, A: Allocator + 'static> From<UniquePointer<T, A>> for Pin<UniquePointer<T, A>> {
+    #[inline]
+    fn from(pointer: UniquePointer<T, A>) -> Self {
+        UniquePointer::into_pin(pointer)
+    }
+}
+
+impl<T: ?Sized, A: Allocator> AsRef<T> for UniquePointer<T, A> {
     #[inline]
     fn as_ref(&self) -> &T {
         // Cast the pointer to a reference and return it
@@ -61,7 +685,7 @@ impl<T> AsRef<T> for UniquePointer<T> {
     }
 }
 
-impl<T> AsMut<T> for UniquePointer<T> {
+impl<T: ?Sized, A: Allocator> AsMut<T> for UniquePointer<T, A> {
     #[inline]
     fn as_mut(&mut self) -> &mut T {
         // Cast the pointer to a mutable reference and return it
@@ -70,7 +694,7 @@ impl<T> AsMut<T> for UniquePointer<T> {
     }
 }
 
-impl<T> Deref for UniquePointer<T> {
+impl<T: ?Sized, A: Allocator> Deref for UniquePointer<T, A> {
     type Target = T;
 
     #[inline]
@@ -79,14 +703,289 @@ impl<T> Deref for UniquePointer<T> {
     }
 }
 
-impl<T> DerefMut for UniquePointer<T> {
+impl<T: ?Sized, A: Allocator> DerefMut for UniquePointer<T, A> {
     #[inline]
     fn deref_mut(&mut self) -> &mut Self::Target {
         self.as_mut()
     }
 }
 
-impl<T: core::fmt::Debug> core::fmt::Debug for UniquePointer<T> {
+impl<T: ?Sized, A: Allocator> Borrow<T> for UniquePointer<T, A> {
+    #[inline]
+    fn borrow(&self) -> &T {
+        self.as_ref()
+    }
+}
+
+impl<T: ?Sized, A: Allocator> BorrowMut<T> for UniquePointer<T, A> {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut T {
+        self.as_mut()
+    }
+}
+
+impl<T> From<T> for UniquePointer<T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: FromStr> FromStr for UniquePointer<T> {
+    type Err = T::Err;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Parse the value first, so nothing is allocated if parsing fails
+        T::from_str(s).map(Self::new)
+    }
+}
+
+impl<T: ?Sized + PartialEq, A: Allocator> PartialEq for UniquePointer<T, A> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        T::eq(self, other)
+    }
+}
+
+impl<T: ?Sized + Eq, A: Allocator> Eq for UniquePointer<T, A> {}
+
+impl<T: ?Sized + PartialOrd, A: Allocator> PartialOrd for UniquePointer<T, A> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        T::partial_cmp(self, other)
+    }
+
+    #[inline]
+    fn lt(&self, other: &Self) -> bool {
+        T::lt(self, other)
+    }
+
+    #[inline]
+    fn le(&self, other: &Self) -> bool {
+        T::le(self, other)
+    }
+
+    #[inline]
+    fn gt(&self, other: &Self) -> bool {
+        T::gt(self, other)
+    }
+
+    #[inline]
+    fn ge(&self, other: &Self) -> bool {
+        T::ge(self, other)
+    }
+}
+
+impl<T: ?Sized + Ord, A: Allocator> Ord for UniquePointer<T, A> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        T::cmp(self, other)
+    }
+}
+
+impl<T: ?Sized + Hash, A: Allocator> Hash for UniquePointer<T, A> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        T::hash(self, state);
+    }
+}
+
+impl<T: ?Sized + Hasher, A: Allocator> Hasher for UniquePointer<T, A> {
+    #[inline]
+    fn finish(&self) -> u64 {
+        T::finish(self)
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        T::write(self, bytes);
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        T::write_u8(self, i);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        T::write_u16(self, i);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        T::write_u32(self, i);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        T::write_u64(self, i);
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        T::write_u128(self, i);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        T::write_usize(self, i);
+    }
+
+    #[inline]
+    fn write_i8(&mut self, i: i8) {
+        T::write_i8(self, i);
+    }
+
+    #[inline]
+    fn write_i16(&mut self, i: i16) {
+        T::write_i16(self, i);
+    }
+
+    #[inline]
+    fn write_i32(&mut self, i: i32) {
+        T::write_i32(self, i);
+    }
+
+    #[inline]
+    fn write_i64(&mut self, i: i64) {
+        T::write_i64(self, i);
+    }
+
+    #[inline]
+    fn write_i128(&mut self, i: i128) {
+        T::write_i128(self, i);
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        T::write_isize(self, i);
+    }
+}
+
+impl<I: ?Sized + Iterator, A: Allocator> Iterator for UniquePointer<I, A> {
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        I::next(self)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        I::size_hint(self)
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        I::nth(self, n)
+    }
+}
+
+impl<I: ?Sized + DoubleEndedIterator, A: Allocator> DoubleEndedIterator for UniquePointer<I, A> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        I::next_back(self)
+    }
+
+    #[inline]
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        I::nth_back(self, n)
+    }
+}
+
+impl<I: ?Sized + ExactSizeIterator, A: Allocator> ExactSizeIterator for UniquePointer<I, A> {
+    #[inline]
+    fn len(&self) -> usize {
+        I::len(self)
+    }
+}
+
+impl<I: ?Sized + FusedIterator, A: Allocator> FusedIterator for UniquePointer<I, A> {}
+
+/// A future that isn't `Unpin` can be polled through a pinned `UniquePointer` instead.
+impl<F: ?Sized + Future + Unpin, A: Allocator> Future for UniquePointer<F, A> {
+    type Output = F::Output;
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // The future is Unpin, so it can be pinned again after moving through the pointer
+        F::poll(Pin::new(&mut *self), cx)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: ?Sized + io::Read, A: Allocator> io::Read for UniquePointer<T, A> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        T::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        T::read_vectored(self, bufs)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        T::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        T::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        T::read_exact(self, buf)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: ?Sized + io::Write, A: Allocator> io::Write for UniquePointer<T, A> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        T::write(self, buf)
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        T::write_vectored(self, bufs)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        T::flush(self)
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        T::write_all(self, buf)
+    }
+
+    #[inline]
+    fn write_fmt(&mut self, args: core::fmt::Arguments<'_>) -> io::Result<()> {
+        T::write_fmt(self, args)
+    }
+}
+
+impl<T: ?Sized + core::fmt::Display, A: Allocator> core::fmt::Display for UniquePointer<T, A> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        T::fmt(self, f)
+    }
+}
+
+impl<T: ?Sized, A: Allocator> core::fmt::Pointer for UniquePointer<T, A> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // Format the address of the value, like a reference to it
+        core::fmt::Pointer::fmt(&self.0, f)
+    }
+}
+
+impl<T: ?Sized + core::fmt::Debug, A: Allocator> core::fmt::Debug for UniquePointer<T, A> {
     #[inline]
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         // Write the UniquePointer as if the value pointed to is stored in it
@@ -94,9 +993,12 @@ impl<T: core::fmt::Debug> core::fmt::Debug for UniquePointer<T> {
     }
 }
 
-impl<T> Drop for UniquePointer<T> {
+impl<T: ?Sized, A: Allocator> Drop for UniquePointer<T, A> {
     #[inline]
     fn drop(&mut self) {
+        // Get the layout of the value, before it is dropped
+        let layout = Layout::for_value(self.as_ref());
+
         // Get the pointer
         let pointer = self.0.as_ptr();
 
@@ -106,14 +1008,14 @@ impl<T> Drop for UniquePointer<T> {
             pointer.drop_in_place();
 
             // Free the memory
-            alloc::alloc::dealloc(pointer.cast(), Layout::new::<T>());
+            allocator::deallocate(&self.2, self.0.cast(), layout);
         };
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use core::fmt::Write;
+    use core::fmt::Write as _;
     use heapless::String;
 
     use super::UniquePointer;
@@ -192,4 +1094,13 @@ mod tests {
         // Check whether the pointer is printed as expected
         assert_eq!(debug_output, expected_output);
     }
+
+    #[test]
+    fn zero_sized() {
+        // Store a zero-sized value in a UniquePointer
+        let pointer = UniquePointer::new(());
+
+        // Check whether nothing was allocated, by checking for a dangling pointer
+        assert_eq!(pointer.0.addr().get(), core::mem::align_of::<()>());
+    }
 }
@@ -0,0 +1,150 @@
+use core::{
+    fmt,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{
+    allocator::{Allocator, Global},
+    counter::{AtomicCounter, Counter, LocalCounter},
+    shared_pointer::CountedPointer,
+};
+
+/// A pointer to a value in the allocation of a `SharedPointer`, that is the only pointer to it.
+///
+/// The value can be mutated until it's shared, which converts it to a `SharedPointer` without
+/// moving the value to a new allocation.
+///
+/// `UniqueCountedPointer::shareable` is an associated function, so it doesn't hide methods of the
+/// value.
+///
+/// The `PhantomData` makes it `UnwindSafe` only if the value is, as it gives mutable access.
+pub struct UniqueCountedPointer<T: ?Sized, A: Allocator = Global, C: Counter = AtomicCounter>(
+    CountedPointer<T, A, C>,
+    PhantomData<T>,
+);
+
+/// A `SharedPointer` that hasn't been shared yet, so the value can still be mutated.
+pub type UniqueSharedPointer<T, A = Global> = UniqueCountedPointer<T, A, AtomicCounter>;
+
+/// A `LocalSharedPointer` that hasn't been shared yet, so the value can still be mutated.
+pub type UniqueLocalSharedPointer<T, A = Global> = UniqueCountedPointer<T, A, LocalCounter>;
+
+#[expect(
+    clippy::non_send_fields_in_send_ty,
+    reason = "The SharedPointer is only shared once the value is shareable"
+)]
+/// Safety: No other pointer to the value or the counters exists, like a `UniquePointer`.
+unsafe impl<T: ?Sized + Send, A: Allocator + Send, C: Counter + Send> Send
+    for UniqueCountedPointer<T, A, C>
+{
+}
+
+/// Safety: Shared access to the pointer only gives shared access to the value and the counters.
+unsafe impl<T: ?Sized + Sync, A: Allocator + Sync, C: Counter + Sync> Sync
+    for UniqueCountedPointer<T, A, C>
+{
+}
+
+/// Moving the pointer doesn't move the value, so it doesn't matter whether `T` is `Unpin`.
+impl<T: ?Sized, A: Allocator, C: Counter> Unpin for UniqueCountedPointer<T, A, C> {}
+
+impl<T, C: Counter> UniqueCountedPointer<T, Global, C> {
+    /// Allocates memory for the counters and the value, and stores the value in it.
+    #[inline]
+    pub fn new(value: T) -> Self {
+        Self::new_in(value, Global)
+    }
+}
+
+impl<T, A: Allocator, C: Counter> UniqueCountedPointer<T, A, C> {
+    /// Allocates memory for the counters and the value with the allocator, and stores the value in
+    /// it.
+    #[inline]
+    pub fn new_in(value: T, allocator: A) -> Self {
+        // Safety: The new SharedPointer is the only pointer to the value
+        unsafe { Self::from_shared(CountedPointer::new_in(value, allocator)) }
+    }
+}
+
+impl<T: ?Sized, A: Allocator, C: Counter> UniqueCountedPointer<T, A, C> {
+    /// Wraps a `SharedPointer`, that is the only pointer to the value.
+    ///
+    /// # Safety
+    /// No other `SharedPointer` or `WeakPointer` to the value may exist.
+    pub(crate) const unsafe fn from_shared(pointer: CountedPointer<T, A, C>) -> Self {
+        Self(pointer, PhantomData)
+    }
+
+    /// Returns the allocator the value is stored in.
+    #[inline]
+    pub const fn allocator(&self) -> &A {
+        self.0.allocator()
+    }
+
+    /// Converts the pointer to a `SharedPointer`, which can be cloned but doesn't give mutable
+    /// access anymore.
+    ///
+    /// The value already has the layout of a `SharedPointer`, so nothing is moved or allocated.
+    #[inline]
+    pub fn shareable(pointer: Self) -> CountedPointer<T, A, C> {
+        pointer.0
+    }
+}
+
+impl<T: Default, C: Counter> Default for UniqueCountedPointer<T, Global, C> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: ?Sized, A: Allocator, C: Counter> From<UniqueCountedPointer<T, A, C>>
+    for CountedPointer<T, A, C>
+{
+    #[inline]
+    fn from(pointer: UniqueCountedPointer<T, A, C>) -> Self {
+        UniqueCountedPointer::shareable(pointer)
+    }
+}
+
+impl<T: ?Sized, A: Allocator, C: Counter> AsRef<T> for UniqueCountedPointer<T, A, C> {
+    #[inline]
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: ?Sized, A: Allocator, C: Counter> AsMut<T> for UniqueCountedPointer<T, A, C> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut T {
+        // Safety: No other pointer to the value exists, and the reference borrows this pointer
+        unsafe { CountedPointer::get_mut_unchecked(&mut self.0) }
+    }
+}
+
+impl<T: ?Sized, A: Allocator, C: Counter> Deref for UniqueCountedPointer<T, A, C> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_ref()
+    }
+}
+
+impl<T: ?Sized, A: Allocator, C: Counter> DerefMut for UniqueCountedPointer<T, A, C> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut()
+    }
+}
+
+impl<T: ?Sized + fmt::Debug, A: Allocator, C: Counter> fmt::Debug
+    for UniqueCountedPointer<T, A, C>
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Write the pointer like the SharedPointer it will become
+        f.write_fmt(format_args!("Unique{}({:?})", C::NAME, self.as_ref()))
+    }
+}
@@ -0,0 +1,254 @@
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use smart_pointers::{AtomicOptionSharedPointer, AtomicSharedPointer, SharedPointer};
+
+/// Counts how many values are alive, to find leaks and double drops.
+struct Tracked<'a> {
+    first: u64,
+    second: u64,
+    alive: &'a AtomicUsize,
+}
+
+impl<'a> Tracked<'a> {
+    fn new(value: u64, alive: &'a AtomicUsize) -> Self {
+        alive.fetch_add(1, Ordering::Relaxed);
+        Self {
+            first: value,
+            second: value,
+            alive,
+        }
+    }
+}
+
+impl Drop for Tracked<'_> {
+    fn drop(&mut self) {
+        self.alive.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn loading_and_storing() {
+    // Store a value in a slot and load it again
+    let value = rand::random::<u64>();
+    let slot = AtomicSharedPointer::new(SharedPointer::new(value));
+    let loaded = slot.load();
+    assert_eq!(*loaded, value);
+    assert_eq!(loaded.reference_count(), 2);
+
+    // Swapping returns the previous pointer
+    let previous = slot.swap(SharedPointer::new(value.wrapping_add(1)));
+    assert_eq!(
+        SharedPointer::as_ptr(&previous),
+        SharedPointer::as_ptr(&loaded)
+    );
+    assert_eq!(*slot.load(), value.wrapping_add(1));
+
+    // Storing drops the previous pointer
+    slot.store(SharedPointer::new(value.wrapping_add(2)));
+    assert_eq!(*slot.load(), value.wrapping_add(2));
+    drop(previous);
+    assert_eq!(loaded.reference_count(), 1);
+
+    // The last stored pointer is returned by into_inner
+    let pointer = slot.into_inner();
+    assert_eq!(*pointer, value.wrapping_add(2));
+    assert_eq!(pointer.reference_count(), 1);
+}
+
+#[test]
+fn comparing_and_exchanging() {
+    // Store a value in a slot
+    let value = rand::random::<u64>();
+    let slot = AtomicSharedPointer::new(SharedPointer::new(value));
+    let current = slot.load();
+
+    // An equal value in a different allocation doesn't match
+    let other = SharedPointer::new(value);
+    let rejected = slot
+        .compare_exchange(&other, SharedPointer::new(0))
+        .unwrap_err();
+    assert_eq!(*rejected, 0);
+    assert_eq!(rejected.reference_count(), 1);
+
+    // The same allocation does
+    let previous = slot.compare_exchange(&current, other.clone()).unwrap();
+    assert_eq!(
+        SharedPointer::as_ptr(&previous),
+        SharedPointer::as_ptr(&current)
+    );
+    assert_eq!(current.reference_count(), 2);
+    assert_eq!(other.reference_count(), 2);
+}
+
+#[test]
+fn optional_pointers() {
+    static SLOT: AtomicOptionSharedPointer<u64> = AtomicOptionSharedPointer::none();
+
+    // An empty slot loads nothing
+    assert!(SLOT.load().is_none());
+
+    // Only an empty slot can be replaced when expecting nothing
+    let value = rand::random::<u64>();
+    assert!(SLOT
+        .compare_exchange(None, Some(SharedPointer::new(value)))
+        .unwrap()
+        .is_none());
+    assert!(SLOT.compare_exchange(None, None).is_err());
+    assert_eq!(SLOT.load().as_deref(), Some(&value));
+
+    // Taking the pointer leaves the slot empty
+    let pointer = SLOT.take().unwrap();
+    assert_eq!(pointer.reference_count(), 1);
+    assert!(SLOT.load().is_none());
+    assert!(AtomicOptionSharedPointer::<u64>::default()
+        .into_inner()
+        .is_none());
+}
+
+#[test]
+fn dropping() {
+    let alive = AtomicUsize::new(0);
+
+    // Dropping a slot drops the stored value
+    let slot = AtomicSharedPointer::new(SharedPointer::new(Tracked::new(1, &alive)));
+    slot.store(SharedPointer::new(Tracked::new(2, &alive)));
+    assert_eq!(alive.load(Ordering::Relaxed), 1);
+    drop(slot);
+    assert_eq!(alive.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn concurrent_load_and_store() {
+    let alive = AtomicUsize::new(0);
+    let slot = AtomicSharedPointer::new(SharedPointer::new(Tracked::new(0, &alive)));
+
+    thread::scope(|scope| {
+        // Readers should always see a completely initialized value
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..20_000 {
+                    let loaded = slot.load();
+                    assert_eq!(loaded.first, loaded.second);
+                }
+            });
+        }
+
+        // While writers keep replacing it
+        for writer in 0..2 {
+            let (slot, alive) = (&slot, &alive);
+            scope.spawn(move || {
+                for value in 0..20_000 {
+                    let pointer = SharedPointer::new(Tracked::new(value * 2 + writer, alive));
+                    if value % 2 == 0 {
+                        slot.store(pointer);
+                    } else {
+                        drop(slot.swap(pointer));
+                    }
+                }
+            });
+        }
+    });
+
+    // Only the stored value should be alive, with no references left behind
+    assert_eq!(alive.load(Ordering::Relaxed), 1);
+    assert_eq!(slot.load().reference_count(), 2);
+    drop(slot);
+    assert_eq!(alive.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn concurrent_saturated_loads() {
+    let alive = AtomicUsize::new(0);
+    let slot = AtomicSharedPointer::new(SharedPointer::new(Tracked::new(0, &alive)));
+
+    thread::scope(|scope| {
+        // More readers than borrows, so some of them have to announce the value instead
+        for _ in 0..16 {
+            scope.spawn(|| {
+                for _ in 0..5_000 {
+                    let loaded = slot.load();
+                    assert_eq!(loaded.first, loaded.second);
+                }
+            });
+        }
+
+        // While a writer keeps replacing it
+        scope.spawn(|| {
+            for value in 0..5_000 {
+                slot.store(SharedPointer::new(Tracked::new(value, &alive)));
+            }
+        });
+    });
+
+    // Only the stored value should be alive, with no references left behind
+    assert_eq!(alive.load(Ordering::Relaxed), 1);
+    assert_eq!(slot.load().reference_count(), 2);
+    drop(slot);
+    assert_eq!(alive.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn concurrent_compare_exchange() {
+    let slot = AtomicSharedPointer::new(SharedPointer::new(0_u64));
+
+    // Increment the stored value from multiple threads, retrying if another thread was faster
+    thread::scope(|scope| {
+        for _ in 0..8 {
+            scope.spawn(|| {
+                for _ in 0..2_000 {
+                    let mut current = slot.load();
+                    loop {
+                        let new = SharedPointer::new(*current + 1);
+                        match slot.compare_exchange(&current, new) {
+                            Ok(_) => break,
+                            Err(_) => current = slot.load(),
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    // No increment should be lost
+    assert_eq!(*slot.load(), 16_000);
+}
+
+#[test]
+fn concurrent_reuse() {
+    // Storing the same allocations again, while readers are borrowing them, shouldn't confuse the
+    // counting of the borrows
+    let first = SharedPointer::new(1_u64);
+    let second = SharedPointer::new(2_u64);
+    let slot = AtomicOptionSharedPointer::new(Some(first.clone()));
+
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..20_000 {
+                    let loaded = slot.load();
+                    assert!(matches!(loaded.as_deref(), None | Some(1 | 2)));
+                }
+            });
+        }
+
+        for _ in 0..2 {
+            scope.spawn(|| {
+                for round in 0..20_000 {
+                    match round % 3 {
+                        0 => slot.store(Some(first.clone())),
+                        1 => slot.store(Some(second.clone())),
+                        _ => drop(slot.take()),
+                    }
+                }
+            });
+        }
+    });
+
+    // Only the references of the slot and the original pointers should be left
+    drop(slot);
+    assert_eq!(first.reference_count(), 1);
+    assert_eq!(second.reference_count(), 1);
+}
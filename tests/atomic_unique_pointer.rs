@@ -0,0 +1,110 @@
+mod common;
+
+use std::{
+    ptr,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use smart_pointers::{AtomicUniquePointer, UniquePointer};
+
+use common::DropCounter;
+
+#[test]
+fn swapping_and_taking() {
+    // Store a value in a slot and take it out again
+    let value = rand::random::<u64>();
+    let slot = AtomicUniquePointer::new(Some(UniquePointer::new(value)));
+    let pointer = slot.take().unwrap();
+    assert_eq!(*pointer, value);
+    assert!(slot.take().is_none());
+
+    // Swapping returns the previous value
+    assert!(slot.swap(Some(pointer)).is_none());
+    let previous = slot.swap(Some(UniquePointer::new(value.wrapping_add(1))));
+    assert_eq!(previous.as_deref(), Some(&value));
+
+    // The stored value can be mutated through a unique borrow of the slot
+    let mut slot = slot;
+    *slot.get_mut().unwrap() = value;
+    assert_eq!(slot.into_inner().as_deref(), Some(&value));
+}
+
+#[test]
+fn comparing_and_exchanging() {
+    // A value can only be put in an empty slot
+    let slot = AtomicUniquePointer::none();
+    let first = UniquePointer::new(rand::random::<u32>());
+    let first_address = UniquePointer::as_ptr(&first);
+    assert!(slot
+        .compare_exchange(ptr::null(), Some(first))
+        .unwrap()
+        .is_none());
+    let rejected = slot
+        .compare_exchange(ptr::null(), Some(UniquePointer::new(5)))
+        .unwrap_err();
+    assert_eq!(rejected.as_deref(), Some(&5));
+
+    // The stored value is only replaced if its address is expected
+    assert!(slot.compare_exchange(ptr::dangling(), None).is_err());
+    let first = slot.compare_exchange(first_address, None).unwrap().unwrap();
+    assert_eq!(UniquePointer::as_ptr(&first), first_address);
+    assert!(slot.take().is_none());
+}
+
+#[test]
+fn dropping() {
+    let drops = AtomicUsize::new(0);
+
+    // Storing drops the previous value
+    let slot = AtomicUniquePointer::from(UniquePointer::new(DropCounter(&drops)));
+    slot.store(Some(UniquePointer::new(DropCounter(&drops))));
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+
+    // Dropping the slot drops the stored value
+    drop(slot);
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+
+    // An empty slot has nothing to drop
+    drop(AtomicUniquePointer::<DropCounter>::default());
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn concurrent_handoff() {
+    static MAILBOX: AtomicUniquePointer<Vec<u64>> = AtomicUniquePointer::none();
+    let received = AtomicUsize::new(0);
+
+    thread::scope(|scope| {
+        // Producers put jobs in the mailbox, whenever it's empty
+        for producer in 0..4 {
+            scope.spawn(move || {
+                for job in 0..1_000 {
+                    let mut pointer = Some(UniquePointer::new(vec![producer, job]));
+                    while let Err(rejected) = MAILBOX.compare_exchange(ptr::null(), pointer) {
+                        pointer = rejected;
+                        thread::yield_now();
+                    }
+                }
+            });
+        }
+
+        // Consumers take them out again
+        for _ in 0..2 {
+            scope.spawn(|| {
+                while received.load(Ordering::Relaxed) < 4_000 {
+                    if let Some(job) = MAILBOX.take() {
+                        assert_eq!(job.len(), 2);
+                        received.fetch_add(1, Ordering::Relaxed);
+                    } else {
+                        thread::yield_now();
+                    }
+                }
+            });
+        }
+    });
+
+    // Every job should be received exactly once
+    assert_eq!(received.load(Ordering::Relaxed), 4_000);
+    assert!(MAILBOX.take().is_none());
+}
@@ -0,0 +1,83 @@
+//! Helpers shared by the integration tests, every test crate only uses some of them.
+#![allow(dead_code)]
+
+use std::{
+    alloc::Layout,
+    marker::PhantomPinned,
+    ptr::{self, NonNull},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use smart_pointers::{AllocError, Allocator, Global, Initialized, Slot};
+
+/// Counts how often it was dropped, to check whether a pointer drops its value exactly once.
+pub struct DropCounter<'a>(pub &'a AtomicUsize);
+
+impl Drop for DropCounter<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Counts the allocations that are still alive, to check whether memory is returned.
+#[derive(Default)]
+pub struct CountingAllocator(pub AtomicUsize);
+
+unsafe impl Allocator for CountingAllocator {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, pointer: NonNull<u8>, layout: Layout) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+        unsafe { Global.deallocate(pointer, layout) }
+    }
+}
+
+/// An allocator without any memory.
+pub struct FailingAllocator;
+
+unsafe impl Allocator for FailingAllocator {
+    fn allocate(&self, _: Layout) -> Result<NonNull<u8>, AllocError> {
+        Err(AllocError)
+    }
+
+    unsafe fn deallocate(&self, _: NonNull<u8>, _: Layout) {
+        unreachable!("Nothing was allocated")
+    }
+}
+
+/// A value that stores its own address, so it must never be moved.
+pub struct SelfReferential {
+    pub data: [u64; 32],
+    this: *const SelfReferential,
+    _pinned: PhantomPinned,
+}
+
+impl SelfReferential {
+    /// Initializes the value directly in the slot, with the address of the slot.
+    pub fn write(mut slot: Slot<'_, Self>, data: [u64; 32]) -> Initialized<'_, Self> {
+        let this = slot.as_mut_ptr().cast_const();
+        slot.write(Self {
+            data,
+            this,
+            _pinned: PhantomPinned,
+        })
+    }
+
+    /// Initializes the value field by field, through a pointer to the slot.
+    pub fn write_fields(mut slot: Slot<'_, Self>, data: [u64; 32]) -> Initialized<'_, Self> {
+        let memory = slot.as_mut_ptr();
+        unsafe {
+            (&raw mut (*memory).data).write(data);
+            (&raw mut (*memory).this).write(memory);
+            slot.assume_init()
+        }
+    }
+
+    /// Returns whether the value is still stored at the address it was initialized at.
+    pub fn is_in_place(&self) -> bool {
+        ptr::eq(self.this, self)
+    }
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use std::{
+    fmt::Display,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use smart_pointers::{
+    unsize, AtomicCounter, AtomicCounter32, CountedPointer, Counter, Global, LocalCounter,
+    SaturatingCounter, SharedPointer,
+};
+
+use common::DropCounter;
+
+/// Checks the behaviour every counter has to share.
+fn check_counter<C: Counter>() {
+    // Store a random value and clone the pointer
+    let value = rand::random::<u64>();
+    let mut pointer = CountedPointer::<_, Global, C>::new(value);
+    let cloned_pointer = pointer.clone();
+    assert_eq!(*cloned_pointer, value);
+    assert_eq!(pointer.reference_count(), 2);
+
+    // Weak pointers can be upgraded while the value is alive
+    let weak_pointer = pointer.downgrade();
+    assert_eq!(pointer.weak_count(), 1);
+    assert_eq!(*weak_pointer.upgrade().unwrap(), value);
+
+    // The value is cloned by make_mut while other pointers exist
+    let mutable = CountedPointer::make_mut(&mut pointer);
+    *mutable = mutable.wrapping_add(1);
+    assert_eq!(*cloned_pointer, value);
+    assert_eq!(*pointer, value.wrapping_add(1));
+    assert!(weak_pointer.upgrade().is_some());
+
+    // The last pointer can be unwrapped, after which the weak pointer can't be upgraded
+    assert_eq!(CountedPointer::try_unwrap(cloned_pointer).ok(), Some(value));
+    assert!(weak_pointer.upgrade().is_none());
+    assert_eq!(weak_pointer.weak_count(), 0);
+
+    // The value is dropped with the last strong pointer
+    let drops = AtomicUsize::new(0);
+    let pointer = CountedPointer::<_, Global, C>::new(DropCounter(&drops));
+    let weak_pointer = pointer.downgrade();
+    drop(pointer);
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+    drop(weak_pointer);
+
+    // Unsized values work with every counter
+    let pointer: CountedPointer<dyn Display, Global, C> =
+        unsize!(CountedPointer::<_, Global, C>::new(value));
+    assert_eq!(pointer.to_string(), value.to_string());
+    let pointer = CountedPointer::<str, Global, C>::from("Hello, world!");
+    assert_eq!(&*pointer, "Hello, world!");
+}
+
+#[test]
+fn counters() {
+    check_counter::<AtomicCounter>();
+    check_counter::<AtomicCounter32>();
+    check_counter::<LocalCounter>();
+    check_counter::<SaturatingCounter>();
+}
+
+#[test]
+fn default_counter() {
+    // Without a counter, a CountedPointer is a SharedPointer
+    let pointer: CountedPointer<i32> = SharedPointer::new(5);
+    assert_eq!(format!("{pointer:?}"), "SharedPointer(5)");
+
+    // Other counters are shown by their own name
+    let pointer = CountedPointer::<_, Global, AtomicCounter32>::new(5);
+    assert_eq!(format!("{pointer:?}"), "CountedPointer(5)");
+    assert_eq!(format!("{:?}", pointer.downgrade()), "(CountedWeakPointer)");
+}
+
+#[test]
+fn concurrent_counters() {
+    fn clone_and_drop<C: Counter + Send + Sync>() {
+        let drops = AtomicUsize::new(0);
+        let pointer = CountedPointer::<_, Global, C>::new(DropCounter(&drops));
+
+        // Clone, downgrade and drop the pointer from multiple threads at the same time
+        thread::scope(|scope| {
+            for _ in 0..8 {
+                let pointer = pointer.clone();
+                scope.spawn(move || {
+                    for _ in 0..10_000 {
+                        let weak_pointer = pointer.downgrade();
+                        let cloned_pointers = [pointer.clone(), weak_pointer.upgrade().unwrap()];
+                        drop(cloned_pointers);
+                    }
+                });
+            }
+        });
+
+        // Only the original pointer should be left and the value shouldn't be dropped yet
+        assert_eq!(pointer.reference_count(), 1);
+        assert_eq!(pointer.weak_count(), 0);
+        assert_eq!(drops.load(Ordering::Relaxed), 0);
+        drop(pointer);
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+    }
+
+    clone_and_drop::<AtomicCounter32>();
+    clone_and_drop::<SaturatingCounter>();
+}
@@ -0,0 +1,158 @@
+mod common;
+
+use std::{cell::RefCell, fmt::Display, rc::Rc, sync::atomic::Ordering};
+
+use smart_pointers::{unsize, LocalSharedPointer, LocalWeakPointer};
+
+use common::CountingAllocator;
+
+#[test]
+fn pointer_creation() {
+    // Generate a random value
+    let value = rand::random::<i32>();
+
+    // Store it in a LocalSharedPointer
+    let pointer = LocalSharedPointer::new(value);
+
+    // Check whether the value was stored correctly
+    assert_eq!(*pointer, value);
+    assert_eq!(LocalSharedPointer::<u16>::default().reference_count(), 1);
+}
+
+#[test]
+fn cloning() {
+    // Store a random value in a LocalSharedPointer
+    let value = rand::random::<f64>();
+    let pointer = LocalSharedPointer::new(value);
+
+    // Clones should point to the same value and be counted
+    let cloned_pointer = pointer.clone();
+    assert_eq!(*pointer, *cloned_pointer);
+    assert_eq!(pointer.reference_count(), 2);
+    drop(cloned_pointer);
+    assert_eq!(pointer.reference_count(), 1);
+}
+
+#[test]
+fn weak_pointer_upgrade() {
+    // Store a random value in a LocalSharedPointer and create a LocalWeakPointer to it
+    let value = rand::random::<u64>();
+    let pointer = LocalSharedPointer::new(value);
+    let weak_pointer = pointer.downgrade();
+    assert_eq!(pointer.weak_count(), 1);
+
+    // Upgrade the LocalWeakPointer and check whether it points to the same value
+    let upgraded_pointer = weak_pointer.upgrade().unwrap();
+    assert_eq!(*upgraded_pointer, value);
+    assert_eq!(weak_pointer.reference_count(), 2);
+
+    // Drop all LocalSharedPointers and check whether upgrading fails
+    drop(pointer);
+    drop(upgraded_pointer);
+    assert!(weak_pointer.upgrade().is_none());
+    assert_eq!(weak_pointer.reference_count(), 0);
+    assert_eq!(weak_pointer.weak_count(), 0);
+}
+
+#[test]
+fn weak_pointer_breaks_cycles() {
+    struct Node {
+        parent: RefCell<Option<LocalWeakPointer<Node>>>,
+        children: RefCell<Vec<LocalSharedPointer<Node>>>,
+    }
+
+    // Create a parent and a child pointing back to it
+    let parent = LocalSharedPointer::new(Node {
+        parent: RefCell::new(None),
+        children: RefCell::new(vec![]),
+    });
+    let child = LocalSharedPointer::new(Node {
+        parent: RefCell::new(Some(parent.downgrade())),
+        children: RefCell::new(vec![]),
+    });
+    parent.children.borrow_mut().push(child.clone());
+
+    // Dropping the parent should drop its value, as the child only holds a LocalWeakPointer
+    let weak_parent = child.parent.borrow().clone().unwrap();
+    drop(parent);
+    assert!(weak_parent.upgrade().is_none());
+    assert_eq!(child.reference_count(), 1);
+}
+
+#[test]
+fn unsized_values() {
+    // Slices, strings and trait objects can be stored
+    let values: [u32; 8] = rand::random();
+    let pointer = LocalSharedPointer::<[u32]>::from(&values[..]);
+    let pointer2 = LocalSharedPointer::<str>::from("Hello, world!");
+    let pointer3: LocalSharedPointer<dyn Display> = unsize!(LocalSharedPointer::new(values[0]));
+    assert_eq!(*pointer, values);
+    assert_eq!(&*pointer2, "Hello, world!");
+    assert_eq!(pointer3.to_string(), values[0].to_string());
+
+    // Dropping the last unsized pointer should drop the value
+    let drops = Rc::new(());
+    let pointer: LocalSharedPointer<[Rc<()>]> = LocalSharedPointer::from(vec![drops.clone(); 4]);
+    let cloned_pointer = pointer.clone();
+    drop(pointer);
+    assert_eq!(Rc::strong_count(&drops), 5);
+    drop(cloned_pointer);
+    assert_eq!(Rc::strong_count(&drops), 1);
+}
+
+#[test]
+fn unique_mutation() {
+    // A unique pointer gives mutable access
+    let value = rand::random::<u64>();
+    let mut pointer = LocalSharedPointer::new(vec![value]);
+    LocalSharedPointer::get_mut(&mut pointer)
+        .unwrap()
+        .push(value);
+
+    // A shared value is cloned before it's mutated
+    let cloned_pointer = pointer.clone();
+    assert!(LocalSharedPointer::get_mut(&mut pointer).is_none());
+    LocalSharedPointer::make_mut(&mut pointer).clear();
+    assert!(pointer.is_empty());
+    assert_eq!(*cloned_pointer, [value, value]);
+
+    // The value can only be moved out of the last pointer
+    let pointer = LocalSharedPointer::try_unwrap(pointer).unwrap();
+    assert!(pointer.is_empty());
+    let pointer = cloned_pointer.clone();
+    assert_eq!(LocalSharedPointer::into_inner(pointer), None);
+    assert_eq!(
+        LocalSharedPointer::into_inner(cloned_pointer),
+        Some(vec![value, value])
+    );
+}
+
+#[test]
+fn raw_pointers() {
+    // A pointer can be converted to a raw pointer and back
+    let value = rand::random::<u64>();
+    let raw = LocalSharedPointer::into_raw(LocalSharedPointer::new(value));
+    unsafe { LocalSharedPointer::increment_strong_count(raw) };
+    let pointer = unsafe { LocalSharedPointer::from_raw(raw) };
+    assert_eq!(*pointer, value);
+    assert_eq!(pointer.reference_count(), 2);
+    unsafe { LocalSharedPointer::decrement_strong_count(raw) };
+    assert_eq!(pointer.reference_count(), 1);
+}
+
+#[test]
+fn custom_allocator() {
+    let allocator = CountingAllocator::default();
+
+    // Store a value using the allocator
+    let value = rand::random::<u64>();
+    let pointer = LocalSharedPointer::new_in(value, &allocator);
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 1);
+
+    // The memory should only be returned after the last LocalWeakPointer is dropped
+    let weak_pointer = pointer.downgrade();
+    drop(pointer);
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 1);
+    drop(weak_pointer);
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 0);
+}
@@ -0,0 +1,85 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use smart_pointers::{LocalSharedPointer, MappedPointer, SharedPointer};
+
+struct Pair<'a> {
+    first: u64,
+    second: String,
+    drops: &'a AtomicUsize,
+}
+
+impl Drop for Pair<'_> {
+    fn drop(&mut self) {
+        self.drops.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn mapping() {
+    // Map a pointer to a field of the value
+    let value = rand::random::<u64>();
+    let drops = AtomicUsize::new(0);
+    let pointer = SharedPointer::new(Pair {
+        first: value,
+        second: value.to_string(),
+        drops: &drops,
+    });
+    let first = SharedPointer::map(pointer.clone(), |pair| &pair.first);
+    assert_eq!(*first, value);
+    assert_eq!(first.reference_count(), 2);
+
+    // The mapped pointer points into the original value
+    let second = SharedPointer::map(pointer, |pair| &pair.second);
+    assert_eq!(
+        MappedPointer::as_ptr(&second),
+        &raw const MappedPointer::owner(&first).second
+    );
+
+    // Mapping a mapped pointer keeps the same owner
+    let bytes = MappedPointer::map(second, |string| string.as_bytes());
+    assert_eq!(bytes.as_ref(), value.to_string().as_bytes());
+    assert_eq!(format!("{first:?}"), format!("MappedPointer({value})"));
+
+    // The owner can be taken back
+    let owner = MappedPointer::into_owner(bytes);
+    assert_eq!(owner.first, value);
+    assert_eq!(owner.reference_count(), 2);
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn sharing_the_count() {
+    let drops = AtomicUsize::new(0);
+    let pointer = SharedPointer::new(Pair {
+        first: 1,
+        second: String::new(),
+        drops: &drops,
+    });
+    let weak_pointer = pointer.downgrade();
+
+    // Cloning and dropping mapped pointers changes the count of the original value
+    let mapped = SharedPointer::map(pointer, |pair| &pair.first);
+    let cloned = mapped.clone();
+    assert_eq!(weak_pointer.reference_count(), 2);
+    drop(mapped);
+    assert_eq!(weak_pointer.reference_count(), 1);
+
+    // The whole value is dropped with the last mapped pointer
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    drop(cloned);
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+    assert!(weak_pointer.upgrade().is_none());
+}
+
+#[test]
+fn local_and_unsized() {
+    // Values of any counter and size can be mapped
+    let pointer = LocalSharedPointer::<[u32]>::from(vec![1, 2, 3]);
+    let last = LocalSharedPointer::map(pointer, |slice| slice.last().unwrap());
+    assert_eq!(*last, 3);
+
+    let pointer = SharedPointer::<str>::from("Hello, world!");
+    let word = SharedPointer::map(pointer, |string| &string[7..12]);
+    assert_eq!(&*word, "world");
+    assert_eq!(MappedPointer::owner(&word).len(), 13);
+}
@@ -1,6 +1,24 @@
-use std::{cell::RefCell, sync::RwLock, thread};
+mod common;
 
-use smart_pointers::SharedPointer;
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::{BTreeMap, HashMap, HashSet},
+    ffi::c_void,
+    fmt::Display,
+    marker::PhantomPinned,
+    panic::{self, AssertUnwindSafe},
+    rc::Rc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        RwLock,
+    },
+    thread,
+};
+
+use smart_pointers::{unsize, LocalSharedPointer, SharedPointer, UniquePointer, WeakPointer};
+
+use common::{CountingAllocator, DropCounter, FailingAllocator, SelfReferential};
 
 #[test]
 fn pointer_creation() {
@@ -107,3 +125,747 @@ fn thread_safety() {
     assert!(slice.iter().all(|&value| value == 1));
     assert_eq!(vec.reference_count(), 1);
 }
+
+#[test]
+fn weak_pointer_upgrade() {
+    // Generate a random value
+    let value = rand::random::<u64>();
+
+    // Store it in a SharedPointer and create a WeakPointer to it
+    let pointer = SharedPointer::new(value);
+    let weak_pointer = pointer.downgrade();
+
+    // Check whether the counts were updated
+    assert_eq!(pointer.reference_count(), 1);
+    assert_eq!(pointer.weak_count(), 1);
+
+    // Upgrade the WeakPointer and check whether it points to the same value
+    let upgraded_pointer = weak_pointer.upgrade().unwrap();
+    assert_eq!(*upgraded_pointer, value);
+    assert_eq!(weak_pointer.reference_count(), 2);
+
+    // Drop all SharedPointers and check whether upgrading fails
+    drop(pointer);
+    drop(upgraded_pointer);
+    assert!(weak_pointer.upgrade().is_none());
+    assert_eq!(weak_pointer.reference_count(), 0);
+    assert_eq!(weak_pointer.weak_count(), 0);
+}
+
+#[test]
+fn weak_pointer_drops_value() {
+    let drops = AtomicUsize::new(0);
+
+    // Create a WeakPointer that outlives the SharedPointer
+    let pointer = SharedPointer::new(DropCounter(&drops));
+    let weak_pointer = pointer.downgrade();
+    let weak_pointer2 = weak_pointer.clone();
+
+    // The value should be dropped with the last SharedPointer, not with the last WeakPointer
+    drop(pointer);
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+    drop(weak_pointer);
+    drop(weak_pointer2);
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn weak_pointer_breaks_cycles() {
+    struct Node {
+        parent: RefCell<Option<WeakPointer<Node>>>,
+        children: RefCell<Vec<SharedPointer<Node>>>,
+    }
+
+    // Create a parent and a child pointing back to it
+    let parent = SharedPointer::new(Node {
+        parent: RefCell::new(None),
+        children: RefCell::new(vec![]),
+    });
+    let child = SharedPointer::new(Node {
+        parent: RefCell::new(Some(parent.downgrade())),
+        children: RefCell::new(vec![]),
+    });
+    parent.children.borrow_mut().push(child.clone());
+
+    // Check whether the child can reach the parent
+    let weak_parent = child.parent.borrow().clone().unwrap();
+    assert_eq!(weak_parent.upgrade().unwrap().children.borrow().len(), 1);
+
+    // Dropping the parent should drop its value, as the child only holds a WeakPointer
+    drop(parent);
+    assert!(weak_parent.upgrade().is_none());
+    assert_eq!(child.reference_count(), 1);
+}
+
+#[test]
+fn slices() {
+    // Generate a random slice
+    let values: [u32; 8] = rand::random();
+
+    // Store copies of it in SharedPointers
+    let pointer = SharedPointer::<[u32]>::from(&values[..]);
+    let pointer2 = SharedPointer::<[u32]>::from(values.to_vec());
+    let pointer3 = SharedPointer::<[u32]>::from(SharedPointer::new(values));
+
+    // Check whether the slices were stored correctly
+    assert_eq!(*pointer, values);
+    assert_eq!(*pointer2, values);
+    assert_eq!(*pointer3, values);
+
+    // Check whether clones point to the same slice
+    let cloned_pointer = pointer.clone();
+    assert_eq!(*cloned_pointer, values);
+    assert_eq!(pointer.reference_count(), 2);
+}
+
+#[test]
+fn strings() {
+    // Store a string in SharedPointers
+    let pointer = SharedPointer::<str>::from("Hello, world!");
+    let pointer2 = SharedPointer::<str>::from(String::from("Hello, world!"));
+
+    // Check whether the strings were stored correctly
+    assert_eq!(&*pointer, "Hello, world!");
+    assert_eq!(&*pointer2, "Hello, world!");
+    assert_eq!(&*pointer.clone(), "Hello, world!");
+}
+
+#[test]
+fn trait_objects() {
+    // Generate a random value
+    let value = rand::random::<u16>();
+
+    // Store it in a SharedPointer and convert it to a trait object
+    let pointer: SharedPointer<dyn Display + Send + Sync> = unsize!(SharedPointer::new(value));
+    let cloned_pointer = pointer.clone();
+
+    // Check whether the trait objects behave like the value
+    assert_eq!(pointer.to_string(), value.to_string());
+    assert_eq!(cloned_pointer.to_string(), value.to_string());
+
+    // Check whether WeakPointers to trait objects can be upgraded
+    let weak_pointer = pointer.downgrade();
+    assert_eq!(
+        weak_pointer.upgrade().unwrap().to_string(),
+        value.to_string()
+    );
+    drop(pointer);
+    drop(cloned_pointer);
+    assert!(weak_pointer.upgrade().is_none());
+}
+
+#[test]
+fn unsized_drop() {
+    // Store a reference counted value in a slice, so drops can be counted
+    let value = Rc::new(rand::random::<u8>());
+    let pointer: SharedPointer<[Rc<u8>]> = SharedPointer::from(vec![value.clone(); 4]);
+    let cloned_pointer = pointer.clone();
+    assert_eq!(Rc::strong_count(&value), 5);
+
+    // Dropping the last SharedPointer should drop every element
+    drop(pointer);
+    assert_eq!(Rc::strong_count(&value), 5);
+    drop(cloned_pointer);
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
+#[test]
+fn zero_sized_types() {
+    // Store zero-sized values in SharedPointers
+    let pointer = SharedPointer::new(());
+    let pointer2 = SharedPointer::<[()]>::from(vec![(); 16]);
+    let pointer3 = SharedPointer::<[u64]>::from(Vec::new());
+
+    // Check whether cloning works
+    let cloned_pointers = [pointer.clone(), pointer.clone(), pointer.clone()];
+    assert_eq!(pointer.reference_count(), 4);
+    assert_eq!(pointer2.clone().len(), 16);
+    assert!(pointer3.clone().is_empty());
+
+    // Check whether dropping works
+    drop(cloned_pointers);
+    assert_eq!(pointer.reference_count(), 1);
+    let weak_pointer = pointer.downgrade();
+    drop(pointer);
+    assert!(weak_pointer.upgrade().is_none());
+    drop(weak_pointer);
+    drop(pointer2);
+    drop(pointer3);
+}
+
+#[test]
+fn zero_sized_drop() {
+    // The destructor of a zero-sized value should be called once, by the last SharedPointer
+    let drops = AtomicUsize::new(0);
+    let pointer = SharedPointer::new(DropCounter(&drops));
+    let cloned_pointer = pointer.clone();
+    drop(pointer);
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    drop(cloned_pointer);
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn concurrent_clone_and_drop() {
+    let drops = AtomicUsize::new(0);
+    let pointer = SharedPointer::new(DropCounter(&drops));
+
+    // Clone and drop the pointer from multiple threads at the same time
+    thread::scope(|scope| {
+        for _ in 0..8 {
+            let pointer = pointer.clone();
+            scope.spawn(move || {
+                for _ in 0..10_000 {
+                    let cloned_pointers = [pointer.clone(), pointer.clone()];
+                    drop(cloned_pointers);
+                }
+            });
+        }
+    });
+
+    // Only the original pointer should be left and the value shouldn't be dropped yet
+    assert_eq!(pointer.reference_count(), 1);
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    drop(pointer);
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn concurrent_last_drop() {
+    struct Checked(Vec<usize>);
+
+    impl Drop for Checked {
+        fn drop(&mut self) {
+            // Every write has to be visible to the thread that drops the value
+            assert!(self
+                .0
+                .iter()
+                .enumerate()
+                .all(|(index, &value)| index == value));
+        }
+    }
+
+    for _ in 0..100 {
+        // Let multiple threads race to drop the last SharedPointer
+        let pointer = SharedPointer::new(RwLock::new(Checked(vec![])));
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                let pointer = pointer.clone();
+                scope.spawn(move || {
+                    let mut checked = pointer.write().unwrap();
+                    let length = checked.0.len();
+                    checked.0.push(length);
+                });
+            }
+            drop(pointer);
+        });
+    }
+}
+
+#[test]
+fn concurrent_into_inner() {
+    for _ in 0..100 {
+        // Let multiple threads race to take the value out of the last SharedPointer
+        let value = rand::random::<u64>();
+        let pointer = SharedPointer::new(vec![value]);
+        let pointers = vec![pointer.clone(), pointer.clone(), pointer.clone(), pointer];
+        let values = thread::scope(|scope| {
+            let threads = pointers
+                .into_iter()
+                .map(|pointer| scope.spawn(move || SharedPointer::into_inner(pointer)))
+                .collect::<Vec<_>>();
+            threads
+                .into_iter()
+                .filter_map(|thread| thread.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        // Exactly one thread should get the value
+        assert_eq!(values, [vec![value]]);
+    }
+}
+
+#[test]
+fn concurrent_upgrade_and_drop() {
+    let drops = AtomicUsize::new(0);
+
+    for iteration in 0..100 {
+        // Let threads upgrade WeakPointers while the last SharedPointer is dropped
+        let pointer = SharedPointer::new((DropCounter(&drops), iteration));
+        let weak_pointer = pointer.downgrade();
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                let weak_pointer = weak_pointer.clone();
+                scope.spawn(move || {
+                    // An upgrade either fails or gives access to the intact value
+                    while let Some(pointer) = weak_pointer.upgrade() {
+                        assert_eq!(pointer.1, iteration);
+                    }
+                });
+            }
+            drop(pointer);
+        });
+
+        // The value has to be dropped exactly once
+        assert!(weak_pointer.upgrade().is_none());
+        assert_eq!(
+            drops.load(Ordering::Relaxed),
+            usize::try_from(iteration).unwrap() + 1
+        );
+    }
+}
+
+#[test]
+fn fallible_creation() {
+    // Generate a random value
+    let value = rand::random::<i32>();
+
+    // Try to store it in a SharedPointer
+    let pointer = SharedPointer::try_new(value).unwrap();
+
+    // Check whether the value was stored correctly
+    assert_eq!(*pointer, value);
+
+    // Zero-sized values never fail to be stored
+    assert!(SharedPointer::try_new(()).is_ok());
+}
+
+#[test]
+fn in_place_initialization() {
+    // Initialize a value that refers to itself, directly in the pinned memory
+    let data = rand::random::<[u64; 32]>();
+    let pointer = SharedPointer::pin_with(|slot| SelfReferential::write(slot, data));
+    assert_eq!(pointer.data, data);
+    assert!(pointer.is_in_place());
+
+    // Initialize the value field by field, through a pointer to the memory
+    let pointer = SharedPointer::pin_with(|slot| SelfReferential::write_fields(slot, data));
+    assert_eq!(pointer.data, data);
+    assert!(pointer.is_in_place());
+
+    // A successful fallible initialization returns the pointer
+    let value = rand::random::<u32>();
+    let pointer = SharedPointer::try_new_with(|slot| Ok::<_, ()>(slot.write(value)));
+    assert_eq!(pointer.as_deref(), Ok(&value));
+    let pointer = SharedPointer::try_pin_with(|slot| Ok::<_, ()>(slot.write(value)));
+    assert_eq!(pointer.as_deref(), Ok(&value));
+}
+
+#[test]
+fn pinning() {
+    // A pinned SharedPointer only gives shared access to the value
+    let value = rand::random::<u32>();
+    let pointer = SharedPointer::pin((value, PhantomPinned));
+    assert_eq!(pointer.0, value);
+
+    // Clones of a pinned pointer stay pinned and share the value
+    let pointer = SharedPointer::pin(PhantomPinned);
+    let cloned_pointer = pointer.clone();
+    assert!(std::ptr::eq(&*pointer, &*cloned_pointer));
+}
+
+#[test]
+fn unique_mutation() {
+    // A unique pointer gives mutable access
+    let value = rand::random::<u64>();
+    let mut pointer = SharedPointer::new(value);
+    *SharedPointer::get_mut(&mut pointer).unwrap() += 1;
+    assert_eq!(*pointer, value.wrapping_add(1));
+
+    // Other SharedPointers and WeakPointers prevent mutable access
+    let cloned_pointer = pointer.clone();
+    assert!(SharedPointer::get_mut(&mut pointer).is_none());
+    drop(cloned_pointer);
+    let weak_pointer = pointer.downgrade();
+    assert!(SharedPointer::get_mut(&mut pointer).is_none());
+    drop(weak_pointer);
+    assert!(SharedPointer::get_mut(&mut pointer).is_some());
+
+    // A shared value is cloned before it's mutated
+    let mut pointer = SharedPointer::new(vec![value]);
+    let cloned_pointer = pointer.clone();
+    SharedPointer::make_mut(&mut pointer).push(value);
+    assert_eq!(*pointer, [value, value]);
+    assert_eq!(*cloned_pointer, [value]);
+    assert_eq!(cloned_pointer.reference_count(), 1);
+
+    // A unique value is mutated in place
+    let address = SharedPointer::as_ptr(&pointer);
+    SharedPointer::make_mut(&mut pointer).push(value);
+    assert_eq!(SharedPointer::as_ptr(&pointer), address);
+
+    // WeakPointers keep pointing to the original value
+    let weak_pointer = pointer.downgrade();
+    SharedPointer::make_mut(&mut pointer).clear();
+    assert!(pointer.is_empty());
+    assert!(weak_pointer.upgrade().is_none());
+}
+
+#[test]
+fn unwrapping() {
+    // The value can only be moved out of the last SharedPointer
+    let value = rand::random::<u64>();
+    let pointer = SharedPointer::new(value);
+    let cloned_pointer = pointer.clone();
+    let pointer = SharedPointer::try_unwrap(pointer).unwrap_err();
+    assert_eq!(*pointer, value);
+    drop(cloned_pointer);
+    assert_eq!(SharedPointer::try_unwrap(pointer).ok(), Some(value));
+
+    // Only the last pointer returns the value
+    let pointer = SharedPointer::new(value);
+    let cloned_pointer = pointer.clone();
+    assert_eq!(SharedPointer::into_inner(pointer), None);
+    assert_eq!(SharedPointer::into_inner(cloned_pointer), Some(value));
+
+    // A shared value is cloned instead
+    let pointer = SharedPointer::new(vec![value]);
+    let cloned_pointer = pointer.clone();
+    assert_eq!(SharedPointer::unwrap_or_clone(pointer), [value]);
+    assert_eq!(SharedPointer::unwrap_or_clone(cloned_pointer), [value]);
+
+    // WeakPointers don't prevent moving the value out, but can't upgrade afterwards
+    let pointer = SharedPointer::new(value);
+    let weak_pointer = pointer.downgrade();
+    assert_eq!(SharedPointer::try_unwrap(pointer).ok(), Some(value));
+    assert!(weak_pointer.upgrade().is_none());
+}
+
+#[repr(align(64))]
+#[derive(Debug, PartialEq)]
+struct Aligned(u64);
+
+#[test]
+fn raw_pointers() {
+    // A pointer can be passed around as a void pointer and converted back
+    let value = rand::random::<u64>();
+    let pointer = SharedPointer::new(Aligned(value));
+    let address = SharedPointer::as_ptr(&pointer);
+    assert!(std::ptr::eq(address, &*pointer));
+    let raw = SharedPointer::into_raw(pointer).cast::<c_void>();
+    assert_eq!(raw.cast(), address);
+    let pointer = unsafe { SharedPointer::from_raw(raw.cast::<Aligned>()) };
+    assert_eq!(*pointer, Aligned(value));
+
+    // The strong count can be changed through a raw pointer
+    let weak_pointer = pointer.downgrade();
+    let raw = SharedPointer::into_raw(pointer);
+    unsafe { SharedPointer::increment_strong_count(raw) };
+    assert_eq!(weak_pointer.reference_count(), 2);
+    unsafe { SharedPointer::decrement_strong_count(raw) };
+    assert_eq!(weak_pointer.reference_count(), 1);
+    unsafe { SharedPointer::decrement_strong_count(raw) };
+    assert!(weak_pointer.upgrade().is_none());
+
+    // Unsized values keep their metadata
+    let pointer: SharedPointer<dyn Display> = unsize!(SharedPointer::new(value));
+    let pointer = unsafe { SharedPointer::from_raw(SharedPointer::into_raw(pointer)) };
+    assert_eq!(pointer.to_string(), value.to_string());
+    let pointer: SharedPointer<str> = SharedPointer::from("Hello, World!");
+    let pointer = unsafe { SharedPointer::from_raw(SharedPointer::into_raw(pointer)) };
+    assert_eq!(&*pointer, "Hello, World!");
+
+    // A leaked value lives for the rest of the program
+    let pointer = SharedPointer::new(value);
+    let cloned_pointer = pointer.clone();
+    let leaked: &'static u64 = SharedPointer::leak(pointer);
+    assert_eq!(*leaked, value);
+    assert_eq!(cloned_pointer.reference_count(), 2);
+}
+
+#[test]
+fn custom_allocator() {
+    let allocator = CountingAllocator::default();
+
+    // Store a value using the allocator
+    let value = rand::random::<u64>();
+    let pointer = SharedPointer::new_in(value, &allocator);
+    let cloned_pointer = pointer.clone();
+    assert_eq!(*cloned_pointer, value);
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 1);
+
+    // A WeakPointer should keep the memory allocated, after the value is dropped
+    let weak_pointer = pointer.downgrade();
+    drop(pointer);
+    drop(cloned_pointer);
+    assert!(weak_pointer.upgrade().is_none());
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 1);
+
+    // Dropping the last WeakPointer should return the memory to the allocator
+    drop(weak_pointer);
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn cyclic_creation() {
+    struct Node {
+        value: u32,
+        this: WeakPointer<Node>,
+    }
+
+    // The WeakPointer can't be upgraded before the value exists
+    let value = rand::random::<u32>();
+    let pointer = SharedPointer::new_cyclic(|this| {
+        assert!(this.upgrade().is_none());
+        assert_eq!(this.reference_count(), 0);
+        Node {
+            value,
+            this: this.clone(),
+        }
+    });
+
+    // Afterwards it points to the node itself
+    let upgraded_pointer = pointer.this.upgrade().unwrap();
+    assert_eq!(upgraded_pointer.value, value);
+    assert_eq!(
+        SharedPointer::as_ptr(&upgraded_pointer),
+        SharedPointer::as_ptr(&pointer)
+    );
+    assert_eq!(pointer.reference_count(), 2);
+    assert_eq!(pointer.weak_count(), 1);
+
+    // Dropping the node doesn't leak, even though it points to itself
+    struct Cycle<'a>(WeakPointer<Cycle<'a>, &'a CountingAllocator>);
+
+    let allocator = CountingAllocator::default();
+    let pointer = SharedPointer::new_cyclic_in(|this| Cycle(this.clone()), &allocator);
+    assert_eq!(pointer.0.reference_count(), 1);
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 1);
+    drop(pointer);
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 0);
+
+    // A panicking closure should free the memory, and later upgrades still fail
+    let escaped = RefCell::new(None);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        SharedPointer::<u32, _>::new_cyclic_in(
+            |this| {
+                *escaped.borrow_mut() = Some(this.clone());
+                panic!("construction failed");
+            },
+            &allocator,
+        )
+    }));
+    assert!(result.is_err());
+    let escaped = escaped.into_inner().unwrap();
+    assert!(escaped.upgrade().is_none());
+    drop(escaped);
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn failed_initialization() {
+    let allocator = CountingAllocator::default();
+    let drops = Rc::new(());
+
+    // A failed initialization should free the memory and return the error
+    let result = SharedPointer::<u64, _>::try_new_with_in(|_slot| Err("failed"), &allocator);
+    assert_eq!(result.err(), Some("failed"));
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 0);
+
+    // A failed pinned initialization should free the memory as well
+    static PINNED_ALLOCATOR: CountingAllocator = CountingAllocator(AtomicUsize::new(0));
+    let result = SharedPointer::<u64, _>::try_pin_with_in(|_slot| Err("failed"), &PINNED_ALLOCATOR);
+    assert_eq!(result.err(), Some("failed"));
+    assert_eq!(PINNED_ALLOCATOR.0.load(Ordering::Relaxed), 0);
+
+    // A value that was initialized before the failure should be dropped
+    let result = SharedPointer::try_new_with_in(
+        |slot| {
+            let _initialized = slot.write(Rc::clone(&drops));
+            Err(())
+        },
+        &allocator,
+    );
+    assert!(result.is_err());
+    assert_eq!(Rc::strong_count(&drops), 1);
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 0);
+
+    // A panicking initialization should drop the value and free the memory as well
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        SharedPointer::new_with_in(
+            |slot| {
+                let _initialized = slot.write(Rc::clone(&drops));
+                panic!("initialization failed");
+            },
+            &allocator,
+        )
+    }));
+    assert!(result.is_err());
+    assert_eq!(Rc::strong_count(&drops), 1);
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn failing_allocator() {
+    // An allocation failure should be returned
+    assert!(SharedPointer::try_new_in(rand::random::<u32>(), FailingAllocator).is_err());
+}
+
+#[test]
+fn formatting() {
+    // Display shows the value, Pointer shows its address
+    let value = rand::random::<i32>();
+    let pointer = SharedPointer::new(value);
+    assert_eq!(format!("{pointer:+}"), format!("{value:+}"));
+    assert_eq!(
+        format!("{pointer:p}"),
+        format!("{:p}", SharedPointer::as_ptr(&pointer))
+    );
+}
+
+#[test]
+fn comparisons() {
+    // Pointers compare like their values, even in different allocations
+    let values: [i16; 16] = rand::random();
+    for first in values {
+        for second in values {
+            let (first_pointer, second_pointer) =
+                (SharedPointer::new(first), SharedPointer::new(second));
+            assert_eq!(first_pointer == second_pointer, first == second);
+            assert_eq!(first_pointer.cmp(&second_pointer), first.cmp(&second));
+            assert_eq!(first_pointer <= second_pointer, first <= second);
+            assert!(!SharedPointer::ptr_eq(&first_pointer, &second_pointer));
+        }
+    }
+
+    // Only clones point to the same value
+    let pointer = SharedPointer::new(f64::NAN);
+    let cloned_pointer = pointer.clone();
+    assert!(SharedPointer::ptr_eq(&pointer, &cloned_pointer));
+
+    // Which doesn't make them equal, if the value isn't equal to itself
+    assert!(pointer != cloned_pointer);
+    assert_eq!(pointer.partial_cmp(&cloned_pointer), None);
+
+    // Unsized pointers to the same value are equal, whatever their metadata is
+    let pointer: SharedPointer<dyn Display> = unsize!(pointer);
+    let cloned_pointer: SharedPointer<dyn Display> = unsize!(cloned_pointer);
+    assert!(SharedPointer::ptr_eq(&pointer, &cloned_pointer));
+}
+
+#[test]
+fn pointer_equality() {
+    /// Counts how often it was compared, like a value that's expensive to compare.
+    struct Compared<'a>(&'a AtomicUsize);
+
+    impl PartialEq for Compared<'_> {
+        fn eq(&self, _other: &Self) -> bool {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            true
+        }
+    }
+
+    impl Eq for Compared<'_> {}
+
+    // Comparing clones still compares the values, even if the value is Eq
+    let comparisons = AtomicUsize::new(0);
+    let pointer = SharedPointer::new(Compared(&comparisons));
+    let cloned_pointer = pointer.clone();
+    assert!(pointer == cloned_pointer);
+    assert_eq!(comparisons.load(Ordering::Relaxed), 1);
+
+    // Checking ptr_eq first skips the comparison for clones
+    assert!(SharedPointer::ptr_eq(&pointer, &cloned_pointer) || pointer == cloned_pointer);
+    assert_eq!(comparisons.load(Ordering::Relaxed), 1);
+
+    // And only falls back to comparing the values for different allocations
+    let other_pointer = SharedPointer::new(Compared(&comparisons));
+    assert!(SharedPointer::ptr_eq(&pointer, &other_pointer) || pointer == other_pointer);
+    assert_eq!(comparisons.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn hashing() {
+    // Pointers can be used as keys, and found by the values through Borrow
+    let value = rand::random::<u64>();
+    let mut set = HashSet::new();
+    set.insert(SharedPointer::new(value));
+    set.insert(SharedPointer::new(value));
+    assert_eq!(set.len(), 1);
+    assert!(set.contains(&value));
+
+    let mut map = BTreeMap::new();
+    map.insert(SharedPointer::<str>::from("first"), 1);
+    map.insert(SharedPointer::<str>::from("second"), 2);
+    assert_eq!(map.get("first"), Some(&1));
+    assert_eq!(map.keys().next().map(|key| &**key), Some("first"));
+}
+
+#[test]
+fn conversions() {
+    // Values, boxes and unique pointers can be converted to shared pointers
+    let value = rand::random::<u32>();
+    assert_eq!(*SharedPointer::from(value), value);
+    assert_eq!(*SharedPointer::<u32>::from(Box::new(value)), value);
+    assert_eq!(
+        *SharedPointer::<u32>::from(UniquePointer::new(value)),
+        value
+    );
+
+    // Unsized values keep their metadata when they are moved out of a Box
+    let boxed: Box<dyn Display> = Box::new(value);
+    let pointer: SharedPointer<dyn Display> = SharedPointer::from(boxed);
+    assert_eq!(pointer.to_string(), value.to_string());
+    let values = rand::random::<[u64; 8]>();
+    let boxed: Box<[u64]> = Box::new(values);
+    assert_eq!(*SharedPointer::<[u64]>::from(boxed), values);
+    let boxed: Box<str> = Box::from("Hello, world!");
+    assert_eq!(&*SharedPointer::<str>::from(boxed), "Hello, world!");
+
+    // The value is only dropped once, by the last SharedPointer
+    let drops = AtomicUsize::new(0);
+    let boxed: Box<[DropCounter]> = Box::new([DropCounter(&drops), DropCounter(&drops)]);
+    let pointer = SharedPointer::<[DropCounter]>::from(boxed);
+    assert_eq!(drops.load(Ordering::Relaxed), 0);
+    drop(pointer);
+    assert_eq!(drops.load(Ordering::Relaxed), 2);
+
+    // The value is moved to a new allocation, the old one is freed
+    let allocator = CountingAllocator::default();
+    let drops = Rc::new(());
+    let pointer = UniquePointer::new_in(Rc::clone(&drops), &allocator);
+    let shared_pointer = SharedPointer::<Rc<()>, _>::from(pointer);
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 1);
+    assert_eq!(Rc::strong_count(&drops), 2);
+    drop(shared_pointer);
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 0);
+    assert_eq!(Rc::strong_count(&drops), 1);
+
+    // A zero-sized value has no allocation to free, only the counters are allocated
+    let pointer = SharedPointer::<(), _>::from(UniquePointer::new_in((), &allocator));
+    assert_eq!(*pointer, ());
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn downcasting() {
+    // Store components of different types as trait objects
+    let value = rand::random::<u64>();
+    let mut registry: HashMap<&str, SharedPointer<dyn Any + Send + Sync>> = HashMap::new();
+    registry.insert("number", unsize!(SharedPointer::new(value)));
+    registry.insert("name", unsize!(SharedPointer::new(String::from("plugin"))));
+
+    // The concrete type can be checked through a reference
+    assert_eq!(registry["number"].downcast_ref::<u64>(), Some(&value));
+    assert_eq!(registry["name"].downcast_ref::<u64>(), None);
+
+    // Downcasting keeps the allocation and the reference count
+    let component = registry["number"].clone();
+    let address = SharedPointer::as_ptr(&component).addr();
+    let component = component.downcast::<String>().unwrap_err();
+    let number = component.downcast::<u64>().unwrap();
+    assert_eq!(*number, value);
+    assert_eq!(SharedPointer::as_ptr(&number).addr(), address);
+    assert_eq!(number.reference_count(), 2);
+
+    // Dropping the registry leaves the downcast pointer as the only one
+    let weak_pointer = registry["name"].downgrade();
+    drop(registry);
+    assert_eq!(number.reference_count(), 1);
+    assert!(weak_pointer.upgrade().is_none());
+
+    // Pointers that stay on one thread can be downcast as well
+    let pointer: LocalSharedPointer<dyn Any> = unsize!(LocalSharedPointer::new(Rc::new(value)));
+    let pointer = pointer.downcast::<Rc<u64>>().unwrap();
+    assert_eq!(**pointer, value);
+}
@@ -1,6 +1,23 @@
-use std::any::type_name_of_val;
+mod common;
 
-use smart_pointers::UniquePointer;
+use std::{
+    any::{type_name_of_val, Any},
+    borrow::{Borrow, BorrowMut},
+    collections::{hash_map::DefaultHasher, BTreeSet, HashMap},
+    ffi::c_void,
+    fmt::Display,
+    future::Future,
+    hash::{Hash, Hasher},
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    rc::Rc,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::{Context, Poll, Waker},
+};
+
+use smart_pointers::{unsize, UniquePointer};
+
+use common::{CountingAllocator, DropCounter, FailingAllocator, SelfReferential};
 
 #[test]
 fn pointer_creation() {
@@ -78,3 +95,622 @@ fn debug() {
     // Check whether the pointer is printed as expected
     assert_eq!(format!("{:?}", pointer), format!("UniquePointer({value})"));
 }
+
+#[test]
+fn slices() {
+    // Generate a random slice
+    let values: [u32; 8] = rand::random();
+
+    // Store copies of it in UniquePointers
+    let pointer = UniquePointer::<[u32]>::from(&values[..]);
+    let pointer2 = UniquePointer::<[u32]>::from(values.to_vec());
+    let pointer3 = UniquePointer::<[u32]>::from(UniquePointer::new(values));
+
+    // Check whether the slices were stored correctly
+    assert_eq!(*pointer, values);
+    assert_eq!(*pointer2, values);
+    assert_eq!(*pointer3, values);
+
+    // Check whether cloning keeps the slice intact
+    assert_eq!(*pointer.clone(), values);
+}
+
+#[test]
+fn strings() {
+    // Store a string in UniquePointers
+    let pointer = UniquePointer::<str>::from("Hello, world!");
+    let pointer2 = UniquePointer::<str>::from(String::from("Hello, world!"));
+
+    // Check whether the strings were stored correctly
+    assert_eq!(&*pointer, "Hello, world!");
+    assert_eq!(&*pointer2, "Hello, world!");
+    assert_eq!(&*pointer.clone(), "Hello, world!");
+}
+
+#[test]
+fn trait_objects() {
+    // Generate a random value
+    let value = rand::random::<u16>();
+
+    // Store it in a UniquePointer and convert it to a trait object
+    let mut pointer: UniquePointer<dyn Display> = unsize!(UniquePointer::new(value));
+
+    // Check whether the trait object behaves like the value
+    assert_eq!(pointer.to_string(), value.to_string());
+
+    // Replace the pointer with one to a value of another type
+    pointer = unsize!(UniquePointer::new("value"));
+    assert_eq!(pointer.to_string(), "value");
+}
+
+#[test]
+fn unsized_drop() {
+    // Store a reference counted value in a slice, so drops can be counted
+    let value = Rc::new(rand::random::<u8>());
+    let pointer: UniquePointer<[Rc<u8>]> = UniquePointer::from(vec![value.clone(); 4]);
+    assert_eq!(Rc::strong_count(&value), 5);
+
+    // Dropping the slice should drop every element
+    drop(pointer);
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
+#[test]
+fn zero_sized_types() {
+    #[repr(align(64))]
+    struct Aligned;
+
+    // Store zero-sized values in UniquePointers
+    let pointer = UniquePointer::new(());
+    let pointer2 = UniquePointer::new(Aligned);
+    let pointer3 = UniquePointer::<[u64]>::from(Vec::new());
+    let pointer4 = UniquePointer::<[()]>::from(vec![(); 16]);
+
+    // Check whether the pointers are aligned and the slices have the right length
+    assert_eq!(std::ptr::from_ref(&*pointer2).addr() % 64, 0);
+    assert!(pointer3.is_empty());
+    assert_eq!(pointer4.len(), 16);
+
+    // Check whether cloning and dropping works
+    assert_eq!(*pointer.clone(), ());
+    drop(pointer);
+    drop(pointer2);
+    drop(pointer3);
+    drop(pointer4);
+}
+
+#[test]
+fn zero_sized_drop() {
+    // The destructor of a zero-sized value should still be called
+    let drops = AtomicUsize::new(0);
+    let pointer = UniquePointer::new(DropCounter(&drops));
+    let pointer2: UniquePointer<[DropCounter]> =
+        UniquePointer::from(vec![DropCounter(&drops), DropCounter(&drops)]);
+    drop(pointer);
+    drop(pointer2);
+    assert_eq!(drops.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+fn fallible_creation() {
+    // Generate a random value
+    let value = rand::random::<i32>();
+
+    // Try to store it in a UniquePointer
+    let pointer = UniquePointer::try_new(value).unwrap();
+
+    // Check whether the value was stored correctly
+    assert_eq!(*pointer, value);
+
+    // Zero-sized values never fail to be stored
+    assert!(UniquePointer::try_new(()).is_ok());
+}
+
+#[test]
+fn uninitialized_creation() {
+    // Initialize the value in place, without copying it afterwards
+    let mut pointer = UniquePointer::<[u64; 32]>::new_uninit();
+    let address = pointer.as_ptr().addr();
+    let values = rand::random::<[u64; 32]>();
+    pointer.write(values);
+    let pointer = unsafe { pointer.assume_init() };
+    assert_eq!(*pointer, values);
+    assert_eq!(std::ptr::from_ref(&*pointer).addr(), address);
+
+    // Initialize the elements of a slice one by one
+    let mut pointer = UniquePointer::<String>::new_uninit_slice(16);
+    for (index, element) in pointer.iter_mut().enumerate() {
+        element.write(index.to_string());
+    }
+    let pointer = unsafe { pointer.assume_init() };
+    assert!(pointer
+        .iter()
+        .enumerate()
+        .all(|(index, element)| *element == index.to_string()));
+
+    // Empty slices are supported as well
+    let pointer = UniquePointer::<String>::new_uninit_slice(0);
+    assert!(unsafe { pointer.assume_init() }.is_empty());
+}
+
+#[test]
+fn zeroed_creation() {
+    // A large zeroed allocation shouldn't have to go through the stack
+    let pointer = UniquePointer::<[u8; 1 << 24]>::new_zeroed();
+    let pointer = unsafe { pointer.assume_init() };
+    assert!(pointer.iter().all(|&byte| byte == 0));
+
+    // Zeroed slices contain only zeros
+    let pointer = UniquePointer::<u32>::new_zeroed_slice(1024);
+    let pointer = unsafe { pointer.assume_init() };
+    assert_eq!(pointer.len(), 1024);
+    assert!(pointer.iter().all(|&element| element == 0));
+
+    // A value can be written over the zeroed memory
+    let value = rand::random::<u128>();
+    assert_eq!(
+        *UniquePointer::write(UniquePointer::new_zeroed(), value),
+        value
+    );
+}
+
+#[test]
+fn in_place_initialization() {
+    // Initialize a value that refers to itself, directly in the pinned memory
+    let data = rand::random::<[u64; 32]>();
+    let pointer = UniquePointer::pin_with(|slot| SelfReferential::write(slot, data));
+    assert_eq!(pointer.data, data);
+    assert!(pointer.is_in_place());
+
+    // Initialize the value field by field, through a pointer to the memory
+    let pointer = UniquePointer::pin_with(|slot| SelfReferential::write_fields(slot, data));
+    assert_eq!(pointer.data, data);
+    assert!(pointer.is_in_place());
+
+    // A successful fallible initialization returns the pointer
+    let value = rand::random::<u32>();
+    let pointer = UniquePointer::try_new_with(|slot| Ok::<_, ()>(slot.write(value)));
+    assert_eq!(pointer.as_deref(), Ok(&value));
+    let pointer = UniquePointer::try_pin_with(|slot| Ok::<_, ()>(slot.write(value)));
+    assert_eq!(pointer.as_deref(), Ok(&value));
+}
+
+#[test]
+fn raw_pointers() {
+    // A pointer can be passed around as a void pointer and converted back
+    let value = rand::random::<u64>();
+    let pointer = UniquePointer::new(value);
+    let address = UniquePointer::as_ptr(&pointer);
+    let raw = UniquePointer::into_raw(pointer).cast::<c_void>();
+    assert_eq!(raw.cast_const().cast(), address);
+    let pointer = unsafe { UniquePointer::from_raw(raw.cast::<u64>()) };
+    assert_eq!(*pointer, value);
+
+    // The value can be mutated through a raw pointer
+    let mut pointer = UniquePointer::new(value);
+    unsafe { UniquePointer::as_mut_ptr(&mut pointer).write(!value) };
+    assert_eq!(*pointer, !value);
+
+    // Unsized and zero-sized values keep their metadata and don't allocate
+    let pointer: UniquePointer<str> = UniquePointer::from("Hello, World!");
+    let pointer = unsafe { UniquePointer::from_raw(UniquePointer::into_raw(pointer)) };
+    assert_eq!(&*pointer, "Hello, World!");
+    let pointer =
+        unsafe { UniquePointer::from_raw(UniquePointer::into_raw(UniquePointer::new(()))) };
+    assert_eq!(*pointer, ());
+
+    // A leaked value lives for the rest of the program
+    let leaked: &'static mut u64 = UniquePointer::leak(UniquePointer::new(value));
+    *leaked += 1;
+    assert_eq!(*leaked, value.wrapping_add(1));
+}
+
+/// Creates a future that isn't `Unpin`, as it holds a reference across an await point.
+async fn future(value: u32) -> u32 {
+    let reference = &value;
+    std::future::ready(()).await;
+    *reference
+}
+
+/// Polls the future once, without waking it up again.
+fn poll<F: Future + ?Sized>(future: Pin<&mut F>) -> Poll<F::Output> {
+    future.poll(&mut Context::from_waker(Waker::noop()))
+}
+
+#[test]
+fn pinning() {
+    // Pinning a value that isn't Unpin should work like Pin<Box<T>>
+    let value = rand::random::<u32>();
+    let mut pointer = UniquePointer::pin(future(value));
+    assert_eq!(poll(pointer.as_mut()), Poll::Ready(value));
+
+    // A pointer can be pinned after it was created
+    let mut pointer: Pin<UniquePointer<_>> = UniquePointer::new(future(value)).into();
+    assert_eq!(poll(pointer.as_mut()), Poll::Ready(value));
+
+    // Pinned trait objects can be polled as well
+    let mut pointer: Pin<UniquePointer<dyn Future<Output = u32>>> =
+        UniquePointer::into_pin(unsize!(UniquePointer::new(future(value))));
+    assert_eq!(poll(pointer.as_mut()), Poll::Ready(value));
+}
+
+#[test]
+fn custom_allocator() {
+    let allocator = CountingAllocator::default();
+
+    // Store a value using the allocator
+    let value = rand::random::<u64>();
+    let pointer = UniquePointer::new_in(value, &allocator);
+    assert_eq!(*pointer, value);
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 1);
+
+    // Clones and unsized pointers should use the same allocator
+    let cloned_pointer = pointer.clone();
+    let unsized_pointer: UniquePointer<dyn Display, _> = unsize!(pointer);
+    assert_eq!(unsized_pointer.to_string(), value.to_string());
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 2);
+
+    // Dropping the pointers should return the memory to the allocator
+    drop(cloned_pointer);
+    drop(unsized_pointer);
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 0);
+
+    // Zero-sized values don't allocate
+    let _pointer = UniquePointer::new_in((), &allocator);
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 0);
+
+    // Uninitialized and zeroed memory should come from the allocator as well
+    let uninit_pointer = UniquePointer::<u64, _>::new_uninit_in(&allocator);
+    let zeroed_slice = UniquePointer::<u64, _>::new_zeroed_slice_in(8, &allocator);
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 2);
+    assert_eq!(*UniquePointer::write(uninit_pointer, value), value);
+    assert!(unsafe { zeroed_slice.assume_init() }
+        .iter()
+        .all(|&element| element == 0));
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn failed_initialization() {
+    let allocator = CountingAllocator::default();
+    let drops = Rc::new(());
+
+    // A failed initialization should free the memory and return the error
+    let result = UniquePointer::<u64, _>::try_new_with_in(|_slot| Err("failed"), &allocator);
+    assert_eq!(result.err(), Some("failed"));
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 0);
+
+    // A failed pinned initialization should free the memory as well
+    static PINNED_ALLOCATOR: CountingAllocator = CountingAllocator(AtomicUsize::new(0));
+    let result = UniquePointer::<u64, _>::try_pin_with_in(|_slot| Err("failed"), &PINNED_ALLOCATOR);
+    assert_eq!(result.err(), Some("failed"));
+    assert_eq!(PINNED_ALLOCATOR.0.load(Ordering::Relaxed), 0);
+
+    // A value that was initialized before the failure should be dropped
+    let result = UniquePointer::try_new_with_in(
+        |slot| {
+            let _initialized = slot.write(Rc::clone(&drops));
+            Err(())
+        },
+        &allocator,
+    );
+    assert!(result.is_err());
+    assert_eq!(Rc::strong_count(&drops), 1);
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 0);
+
+    // A panicking initialization should drop the value and free the memory as well
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        UniquePointer::new_with_in(
+            |slot| {
+                let _initialized = slot.write(Rc::clone(&drops));
+                panic!("initialization failed");
+            },
+            &allocator,
+        )
+    }));
+    assert!(result.is_err());
+    assert_eq!(Rc::strong_count(&drops), 1);
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn failing_allocator() {
+    // An allocation failure should be returned
+    assert!(UniquePointer::try_new_in(rand::random::<u32>(), FailingAllocator).is_err());
+}
+
+/// Hashes a value with a hasher that always starts in the same state.
+fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn formatting() {
+    // Display shows the value, like the value itself
+    let value = rand::random::<f64>();
+    let pointer = UniquePointer::new(value);
+    assert_eq!(pointer.to_string(), value.to_string());
+    assert_eq!(format!("{pointer:>30.3}"), format!("{value:>30.3}"));
+
+    // Pointer shows the address of the value
+    assert_eq!(format!("{pointer:p}"), format!("{:p}", &raw const *pointer));
+}
+
+#[test]
+fn comparisons() {
+    // Pointers compare like their values
+    let values: [u32; 16] = rand::random();
+    for first in values {
+        for second in values {
+            let (first_pointer, second_pointer) =
+                (UniquePointer::new(first), UniquePointer::new(second));
+            assert_eq!(first_pointer == second_pointer, first == second);
+            assert_eq!(
+                first_pointer.partial_cmp(&second_pointer),
+                first.partial_cmp(&second)
+            );
+            assert_eq!(first_pointer.cmp(&second_pointer), first.cmp(&second));
+            assert_eq!(first_pointer < second_pointer, first < second);
+            assert_eq!(first_pointer >= second_pointer, first >= second);
+        }
+    }
+
+    // Unordered values stay unordered
+    let not_a_number = UniquePointer::new(f64::NAN);
+    assert!(not_a_number != not_a_number.clone());
+    assert_eq!(not_a_number.partial_cmp(&not_a_number.clone()), None);
+
+    // Sorting pointers sorts the values
+    let mut pointers = values.map(UniquePointer::new);
+    pointers.sort();
+    let mut sorted = values;
+    sorted.sort_unstable();
+    assert_eq!(pointers.map(|pointer| *pointer), sorted);
+}
+
+#[test]
+fn hashing() {
+    // A pointer hashes like its value
+    let value = rand::random::<u64>();
+    assert_eq!(hash_of(&UniquePointer::new(value)), hash_of(&value));
+    assert_eq!(hash_of(&UniquePointer::<str>::from("key")), hash_of("key"));
+
+    // So pointers can be used as keys, and found by the values through Borrow
+    let mut map = HashMap::new();
+    map.insert(UniquePointer::<str>::from("first"), 1);
+    map.insert(UniquePointer::<str>::from("second"), 2);
+    assert_eq!(map.get("second"), Some(&2));
+    assert_eq!(map.get("third"), None);
+
+    let mut set = BTreeSet::new();
+    set.insert(UniquePointer::new(value));
+    assert!(set.contains(&value));
+
+    // A pointer to a hasher hashes like the hasher
+    let mut hasher = UniquePointer::new(DefaultHasher::new());
+    value.hash(&mut hasher);
+    hasher.write_u8(5);
+    let mut expected = DefaultHasher::new();
+    value.hash(&mut expected);
+    expected.write_u8(5);
+    assert_eq!(hasher.finish(), expected.finish());
+}
+
+#[test]
+fn borrowing() {
+    fn push<B: BorrowMut<Vec<u8>>>(mut vec: B, value: u8) -> B {
+        vec.borrow_mut().push(value);
+        vec
+    }
+
+    // A pointer can be borrowed mutably as the value
+    let value = rand::random::<u8>();
+    let pointer = push(UniquePointer::new(Vec::new()), value);
+    let borrowed: &Vec<u8> = pointer.borrow();
+    assert_eq!(borrowed, &[value]);
+}
+
+#[test]
+fn conversions() {
+    // A value can be converted to a pointer
+    let value = rand::random::<i64>();
+    let pointer: UniquePointer<i64> = value.into();
+    assert_eq!(*pointer, value);
+
+    // Or parsed into one, with the errors of the value
+    let parsed: UniquePointer<i64> = value.to_string().parse().unwrap();
+    assert_eq!(parsed, pointer);
+    assert_eq!(
+        "not a number".parse::<UniquePointer<i64>>().unwrap_err(),
+        "not a number".parse::<i64>().unwrap_err()
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn io() {
+    use std::io::{Read, Write};
+
+    // A pointer reads like the reader it points to
+    let bytes: [u8; 32] = rand::random();
+    let mut reader = UniquePointer::new(&bytes[..]);
+    let mut start = [0; 8];
+    reader.read_exact(&mut start).unwrap();
+    let mut rest = Vec::new();
+    assert_eq!(reader.read_to_end(&mut rest).unwrap(), 24);
+    assert_eq!([&start[..], &rest[..]].concat(), bytes);
+
+    // And writes like the writer it points to, even as a trait object
+    let mut writer: UniquePointer<dyn Write> = unsize!(UniquePointer::new(Vec::new()));
+    writer.write_all(&bytes).unwrap();
+    write!(writer, "{}", bytes[0]).unwrap();
+    writer.flush().unwrap();
+    let mut vec = UniquePointer::new(Vec::new());
+    vec.write_all(&bytes).unwrap();
+    assert_eq!(vec.len(), 32);
+}
+
+#[test]
+fn iterators() {
+    // A pointer iterates like the iterator it points to
+    let values: [u8; 16] = rand::random();
+    let pointer = UniquePointer::new(values.into_iter());
+    assert_eq!(pointer.len(), 16);
+    assert!(pointer.eq(values));
+
+    // From both ends
+    let mut pointer = UniquePointer::new(values.into_iter());
+    assert_eq!(pointer.next_back(), values.last().copied());
+    assert_eq!(pointer.nth(2), Some(values[2]));
+    assert_eq!(pointer.nth_back(1), Some(values[13]));
+    assert_eq!(pointer.size_hint(), (10, Some(10)));
+    assert!(pointer.rev().eq(values[3..13].iter().copied().rev()));
+
+    // Trait objects can be iterated as well
+    let mut pointer: UniquePointer<dyn DoubleEndedIterator<Item = u8>> =
+        unsize!(UniquePointer::new(values.into_iter()));
+    assert_eq!(pointer.next(), Some(values[0]));
+    assert_eq!(pointer.next_back(), Some(values[15]));
+    assert_eq!(pointer.by_ref().count(), 14);
+    assert_eq!(pointer.next(), None);
+}
+
+#[test]
+fn futures() {
+    // A future that is Unpin can be polled without pinning the pointer
+    let value = rand::random::<u32>();
+    let mut pointer = UniquePointer::new(std::future::ready(value));
+    assert_eq!(poll(Pin::new(&mut pointer)), Poll::Ready(value));
+
+    // Trait objects as well
+    let mut pointer: UniquePointer<dyn Future<Output = u32> + Unpin> =
+        unsize!(UniquePointer::new(std::future::ready(value)));
+    assert_eq!(poll(Pin::new(&mut pointer)), Poll::Ready(value));
+
+    // Other futures have to be pinned, after which the pointer can be polled
+    let mut pointer = UniquePointer::pin(future(value));
+    assert_eq!(poll(Pin::new(&mut pointer)), Poll::Ready(value));
+}
+
+#[test]
+fn closures() {
+    // Closures can be called through the pointer
+    let value = rand::random::<u64>();
+    let add: UniquePointer<dyn Fn(u64) -> u64> =
+        unsize!(UniquePointer::new(
+            move |other: u64| value.wrapping_add(other)
+        ));
+    assert_eq!(add(1), value.wrapping_add(1));
+
+    // Mutably as well
+    let mut calls = Vec::new();
+    let mut record: UniquePointer<dyn FnMut(u64)> =
+        unsize!(UniquePointer::new(|call| calls.push(call)));
+    record(value);
+    record(add(2));
+    drop(record);
+    assert_eq!(calls, [value, value.wrapping_add(2)]);
+
+    // A closure that can only be called once is called through a Box
+    let string = value.to_string();
+    let take: UniquePointer<dyn FnOnce() -> String> = unsize!(UniquePointer::new(move || string));
+    assert_eq!(UniquePointer::into_box(take)(), value.to_string());
+
+    // Boxes can be converted back without moving the value
+    let boxed: Box<dyn Fn(u64) -> u64> = Box::new(|other| other * 2);
+    let address = (&raw const *boxed).addr();
+    let pointer = UniquePointer::from_box(boxed);
+    assert_eq!(UniquePointer::as_ptr(&pointer).addr(), address);
+    assert_eq!(pointer(value / 4), value / 4 * 2);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn errors() {
+    use std::{error::Error, fmt, num::ParseIntError};
+
+    /// An error caused by another one.
+    #[derive(Debug)]
+    struct Wrapped(ParseIntError);
+
+    impl fmt::Display for Wrapped {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("wrapped")
+        }
+    }
+
+    impl Error for Wrapped {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    fn parse(string: &str) -> Result<u8, UniquePointer<dyn Error + Send + Sync>> {
+        let value = string.parse::<u8>().map_err(Wrapped)?;
+        Ok(value)
+    }
+
+    // Any error is converted by the question mark operator
+    let value = rand::random::<u8>();
+    assert_eq!(parse(&value.to_string()).unwrap(), value);
+    let error = parse("not a number").unwrap_err();
+    let expected = "not a number".parse::<u8>().unwrap_err();
+
+    // The error is shown like the original one, with the same sources
+    assert_eq!(error.to_string(), "wrapped");
+    let source = error.source().unwrap();
+    assert_eq!(source.to_string(), expected.to_string());
+    assert!(source.source().is_none());
+
+    // A pointer to a concrete error is an error as well
+    let error = error.downcast::<ParseIntError>().unwrap_err();
+    let error = error.downcast::<Wrapped>().unwrap();
+    assert_eq!(error.0, expected);
+    assert_eq!(error.source().unwrap().to_string(), expected.to_string());
+
+    // Errors that aren't thread-safe work as well
+    let error: UniquePointer<dyn Error> = Wrapped(expected.clone()).into();
+    assert!(error.is::<Wrapped>());
+    assert_eq!(
+        *error.downcast::<Wrapped>().unwrap().0.kind(),
+        *expected.kind()
+    );
+
+    // Errors that are only Send can be downcast too, like a Box<dyn Error + Send>
+    let error: UniquePointer<dyn Error + Send> = unsize!(UniquePointer::new(Wrapped(expected)));
+    let error = error.downcast::<std::fmt::Error>().unwrap_err();
+    assert_eq!(error.downcast::<Wrapped>().unwrap().to_string(), "wrapped");
+}
+
+#[test]
+fn downcasting() {
+    // The concrete type can be checked through a reference
+    let value = rand::random::<u32>();
+    let pointer: UniquePointer<dyn Any> = unsize!(UniquePointer::new(value));
+    assert_eq!(pointer.downcast_ref::<u32>(), Some(&value));
+    assert_eq!(pointer.downcast_ref::<i32>(), None);
+
+    // Downcasting returns the pointer if the type is wrong, and keeps the allocation otherwise
+    let address = UniquePointer::as_ptr(&pointer).addr();
+    let pointer = pointer.downcast::<i32>().unwrap_err();
+    let pointer = pointer.downcast::<u32>().unwrap();
+    assert_eq!(*pointer, value);
+    assert_eq!(UniquePointer::as_ptr(&pointer).addr(), address);
+
+    // The memory is freed with the concrete pointer
+    let allocator = CountingAllocator::default();
+    let drops = Rc::new(());
+    let pointer: UniquePointer<dyn Any, _> =
+        unsize!(UniquePointer::new_in(Rc::clone(&drops), &allocator));
+    let pointer = pointer.downcast::<Rc<()>>().unwrap();
+    assert_eq!(Rc::strong_count(&pointer), 2);
+    drop(pointer);
+    assert_eq!(Rc::strong_count(&drops), 1);
+    assert_eq!(allocator.0.load(Ordering::Relaxed), 0);
+
+    // Thread-safe trait objects can be downcast as well
+    let pointer: UniquePointer<dyn Any + Send + Sync> = unsize!(UniquePointer::new(value));
+    assert_eq!(pointer.downcast::<u32>().ok().as_deref(), Some(&value));
+}
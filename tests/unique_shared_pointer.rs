@@ -0,0 +1,79 @@
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use smart_pointers::{
+    AtomicCounter32, CountedPointer, Global, LocalSharedPointer, SharedPointer,
+    UniqueCountedPointer, UniqueLocalSharedPointer, UniqueSharedPointer,
+};
+
+use common::DropCounter;
+
+#[test]
+fn mutating_before_sharing() {
+    // Build the value in place, through the unique pointer
+    let mut pointer = UniqueSharedPointer::new(Vec::new());
+    let address = (&raw const *pointer).addr();
+    for _ in 0..10 {
+        pointer.push(rand::random::<u8>());
+    }
+    let expected = pointer.clone();
+
+    // Sharing keeps the value in the same allocation
+    let shared = UniqueSharedPointer::shareable(pointer);
+    assert_eq!(SharedPointer::as_ptr(&shared).addr(), address);
+    assert_eq!(shared.reference_count(), 1);
+    assert_eq!(*shared, expected);
+
+    // The shared pointer counts as usual
+    let cloned = shared.clone();
+    assert_eq!(cloned.reference_count(), 2);
+}
+
+#[test]
+fn converting_back() {
+    // A pointer can only become unique again if no other pointer exists
+    let pointer = SharedPointer::new(rand::random::<u32>());
+    let cloned = pointer.clone();
+    let pointer = SharedPointer::try_into_unique(pointer).unwrap_err();
+    drop(cloned);
+
+    // The same goes for weak pointers
+    let weak_pointer = pointer.downgrade();
+    let pointer = SharedPointer::try_into_unique(pointer).unwrap_err();
+    drop(weak_pointer);
+
+    // After which the value can be mutated again
+    let value = *pointer;
+    let mut unique = SharedPointer::try_into_unique(pointer).unwrap();
+    *unique = value.wrapping_add(1);
+    let pointer: SharedPointer<u32> = unique.into();
+    assert_eq!(*pointer, value.wrapping_add(1));
+}
+
+#[test]
+fn other_counters() {
+    // Every counter has a unique version
+    let mut pointer = UniqueLocalSharedPointer::new(String::from("Hello"));
+    pointer.push_str(", world!");
+    assert_eq!(
+        format!("{pointer:?}"),
+        "UniqueLocalSharedPointer(\"Hello, world!\")"
+    );
+    let shared: LocalSharedPointer<String> = UniqueLocalSharedPointer::shareable(pointer);
+    assert_eq!(*shared, "Hello, world!");
+
+    let pointer = UniqueCountedPointer::<_, Global, AtomicCounter32>::new(5);
+    assert_eq!(format!("{pointer:?}"), "UniqueCountedPointer(5)");
+    let pointer = CountedPointer::try_into_unique(UniqueCountedPointer::shareable(pointer));
+    assert_eq!(pointer.map(|unique| *unique).ok(), Some(5));
+}
+
+#[test]
+fn dropping() {
+    // A unique pointer drops its value like a SharedPointer
+    let drops = AtomicUsize::new(0);
+    drop(UniqueSharedPointer::new(DropCounter(&drops)));
+    assert_eq!(drops.load(Ordering::Relaxed), 1);
+    assert_eq!(*UniqueSharedPointer::<u8>::default(), 0);
+}